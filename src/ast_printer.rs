@@ -0,0 +1,171 @@
+use crate::{
+    expression::{
+        Assign, Binary, Call, Conditional, Expression, Get, Grouping, Interpolation, LiteralExp,
+        Logical, Set, Super, This, Unary, Update, Variable,
+    },
+    token::Literal,
+    token_type::TokenType,
+    visitor::Visitor,
+};
+
+/// Renders expressions as fully parenthesized, Lisp-style text, so that
+/// `-123 * (45.67)` prints as `(* (- 123) (group 45.67))`. Useful to check
+/// how the parser grouped an expression.
+#[derive(Default)]
+pub struct AstPrinter;
+
+impl AstPrinter {
+    pub fn new() -> Self {
+        AstPrinter
+    }
+
+    pub fn print(&mut self, expression: &Expression) -> String {
+        expression.accept(self)
+    }
+
+    fn parenthesize(&mut self, name: &str, expressions: &[&Expression]) -> String {
+        let mut output = format!("({}", name);
+        for expression in expressions {
+            output.push(' ');
+            output.push_str(&expression.accept(self));
+        }
+        output.push(')');
+        output
+    }
+}
+
+impl Visitor<String> for AstPrinter {
+    fn visit_literal(&mut self, literal: &LiteralExp) -> String {
+        let token = literal.token();
+        match (token.token_type(), token.literal()) {
+            (TokenType::STRING | TokenType::INTERPOLATION, Some(Literal::String(string))) => {
+                format!("{:?}", string)
+            }
+            (_, Some(literal)) => literal.to_string(),
+            (_, None) => token.lexeme(),
+        }
+    }
+
+    fn visit_unary(&mut self, unary: &Unary) -> String {
+        self.parenthesize(&unary.operator().lexeme(), &[unary.expression()])
+    }
+
+    fn visit_binary(&mut self, binary: &Binary) -> String {
+        self.parenthesize(
+            &binary.operator().token().lexeme(),
+            &[binary.left(), binary.right()],
+        )
+    }
+
+    fn visit_grouping(&mut self, grouping: &Grouping) -> String {
+        self.parenthesize("group", &[grouping.expression()])
+    }
+
+    fn visit_variable(&mut self, variable: &Variable) -> String {
+        variable.name().lexeme()
+    }
+
+    fn visit_assign(&mut self, assign: &Assign) -> String {
+        let name = format!("= {}", assign.name().lexeme());
+        self.parenthesize(&name, &[assign.value()])
+    }
+
+    fn visit_logical(&mut self, logical: &Logical) -> String {
+        self.parenthesize(
+            &logical.operator().lexeme(),
+            &[logical.left(), logical.right()],
+        )
+    }
+
+    fn visit_conditional(&mut self, conditional: &Conditional) -> String {
+        self.parenthesize(
+            "?:",
+            &[
+                conditional.condition(),
+                conditional.then_branch(),
+                conditional.else_branch(),
+            ],
+        )
+    }
+
+    fn visit_call(&mut self, call: &Call) -> String {
+        let mut expressions = vec![call.callee()];
+        expressions.extend(call.arguments());
+        self.parenthesize("call", &expressions)
+    }
+
+    fn visit_this(&mut self, this: &This) -> String {
+        this.keyword().lexeme()
+    }
+
+    fn visit_get(&mut self, get: &Get) -> String {
+        format!("(. {} {})", get.object().accept(self), get.name().lexeme())
+    }
+
+    fn visit_set(&mut self, set: &Set) -> String {
+        format!(
+            "(= (. {} {}) {})",
+            set.object().accept(self),
+            set.name().lexeme(),
+            set.value().accept(self)
+        )
+    }
+
+    fn visit_super(&mut self, expression: &Super) -> String {
+        format!("(super {})", expression.method().lexeme())
+    }
+
+    fn visit_interpolation(&mut self, interpolation: &Interpolation) -> String {
+        let parts: Vec<&Expression> = interpolation.parts().iter().collect();
+        self.parenthesize("interpolate", &parts)
+    }
+
+    fn visit_update(&mut self, update: &Update) -> String {
+        let operator = update.operator().lexeme();
+        match update.value() {
+            Some(value) => self.parenthesize(&operator, &[update.target(), value]),
+            None if update.is_postfix() => {
+                self.parenthesize(&format!("postfix {}", operator), &[update.target()])
+            }
+            None => self.parenthesize(&operator, &[update.target()]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{expression::Operator, token::Token};
+
+    fn token(lexeme: &str, token_type: TokenType, literal: Option<Literal>) -> Token {
+        Token::new(lexeme.to_string(), token_type, 1, 1, 0, literal)
+    }
+
+    #[test]
+    fn prints_nested_expressions_in_prefix_notation() {
+        let expression = Expression::Binary(Binary::new(
+            Expression::Unary(Unary::new(
+                token("-", TokenType::MINUS, None),
+                Expression::LiteralExp(LiteralExp::new(token(
+                    "123",
+                    TokenType::NUMBER,
+                    Some(Literal::Integer(123)),
+                ))),
+            )),
+            Operator::new(token("*", TokenType::STAR, None)),
+            Expression::Grouping(Grouping::new(
+                Expression::LiteralExp(LiteralExp::new(token(
+                    "45.67",
+                    TokenType::NUMBER,
+                    Some(Literal::Float(45.67)),
+                ))),
+                token("(", TokenType::LEFT_PAREN, None).span(),
+            )),
+        ));
+
+        assert_eq!(
+            AstPrinter::new().print(&expression),
+            "(* (- 123) (group 45.67))"
+        );
+    }
+}
@@ -0,0 +1,666 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, BitAnd, BitOr, BitXor, Mul, Neg, Sub},
+};
+
+/// Arbitrary-precision integer used once a value no longer fits in an `i64`.
+///
+/// The magnitude is stored as base 2^32 limbs, least significant first, with
+/// no trailing zero limbs. Zero has no limbs and is never negative.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+/// Largest result, in bits, that `*`, `**` and `<<` will compute. Anything past
+/// it (about 79,000 decimal digits) would take too long to be useful.
+pub const MAX_BITS: u64 = 1 << 18;
+
+impl BigInt {
+    fn new(negative: bool, limbs: Vec<u32>) -> Self {
+        let mut big = BigInt {
+            negative: negative,
+            limbs: limbs,
+        };
+        while big.limbs.last() == Some(&0) {
+            big.limbs.pop();
+        }
+        if big.limbs.is_empty() {
+            big.negative = false;
+        }
+        big
+    }
+
+    /// Parses unsigned `digits` in the given radix, ignoring anything that
+    /// is not a digit of it (such as `_` separators).
+    pub fn from_digits(digits: &str, radix: u32) -> Self {
+        let mut limbs = Vec::new();
+        for digit in digits.chars().filter_map(|c| c.to_digit(radix)) {
+            let mut carry = digit as u64;
+            for limb in limbs.iter_mut() {
+                let product = *limb as u64 * radix as u64 + carry;
+                *limb = product as u32;
+                carry = product >> 32;
+            }
+            if carry > 0 {
+                limbs.push(carry as u32);
+            }
+        }
+        BigInt::new(false, limbs)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Number of bits in the magnitude, so zero has none.
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len() as u64 * 32 - last.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.limbs.len() > 2 {
+            return None;
+        }
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0i128, |value, limb| value << 32 | *limb as i128);
+        let value = if self.negative { -magnitude } else { magnitude };
+        i64::try_from(value).ok()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0.0, |value, limb| value * 4294967296.0 + *limb as f64);
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Floor division and the matching modulo, so the remainder takes the
+    /// sign of the divisor. Returns `None` when dividing by zero.
+    pub fn div_mod_floor(&self, divisor: &BigInt) -> Option<(BigInt, BigInt)> {
+        if divisor.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = divide_magnitudes(&self.limbs, &divisor.limbs);
+        let quotient = BigInt::new(self.negative != divisor.negative, quotient);
+        let remainder = BigInt::new(self.negative, remainder);
+
+        if !remainder.is_zero() && self.negative != divisor.negative {
+            return Some((&quotient - &BigInt::from(1), &remainder + divisor));
+        }
+        Some((quotient, remainder))
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> BigInt {
+        let mut result = BigInt::from(1);
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    pub fn shift_left(&self, bits: u64) -> BigInt {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let mut limbs = vec![0u32; (bits / 32) as usize];
+        let offset = bits % 32;
+        let mut carry = 0u32;
+        for limb in &self.limbs {
+            let shifted = (*limb as u64) << offset;
+            limbs.push(shifted as u32 | carry);
+            carry = (shifted >> 32) as u32;
+        }
+        limbs.push(carry);
+        BigInt::new(self.negative, limbs)
+    }
+
+    /// Arithmetic shift: rounds toward negative infinity like `>>` on `i64`.
+    pub fn shift_right(&self, bits: u64) -> BigInt {
+        if !self.negative {
+            return BigInt::new(false, shift_right_magnitude(&self.limbs, bits));
+        }
+        // floor(-m / 2^bits) is -(((m - 1) >> bits) + 1).
+        let magnitude = &-self - &BigInt::from(1);
+        let shifted = BigInt::new(false, shift_right_magnitude(&magnitude.limbs, bits));
+        -&(&shifted + &BigInt::from(1))
+    }
+
+    /// Applies `operation` limb by limb to the infinite two's complement
+    /// forms of both numbers, as `&`, `|` and `^` do on `i64`.
+    fn bitwise(&self, other: &BigInt, operation: fn(u32, u32) -> u32) -> BigInt {
+        let len = self.limbs.len().max(other.limbs.len()) + 1;
+        let limbs: Vec<u32> = self
+            .twos_complement(len)
+            .iter()
+            .zip(other.twos_complement(len))
+            .map(|(a, b)| operation(*a, b))
+            .collect();
+
+        if limbs[len - 1] >> 31 == 0 {
+            return BigInt::new(false, limbs);
+        }
+        // Negative: the magnitude is the complement plus one.
+        let complement = BigInt::new(false, limbs.iter().map(|limb| !limb).collect());
+        -&(&complement + &BigInt::from(1))
+    }
+
+    fn twos_complement(&self, len: usize) -> Vec<u32> {
+        let mut limbs = if self.negative {
+            (&-self - &BigInt::from(1)).limbs
+        } else {
+            self.limbs.clone()
+        };
+        limbs.resize(len, 0);
+        if self.negative {
+            for limb in limbs.iter_mut() {
+                *limb = !*limb;
+            }
+        }
+        limbs
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let magnitude = value.unsigned_abs();
+        BigInt::new(value < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.limbs.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_magnitudes(&self.limbs, &other.limbs));
+        }
+
+        match compare_magnitudes(&self.limbs, &other.limbs) {
+            Ordering::Less => {
+                BigInt::new(other.negative, sub_magnitudes(&other.limbs, &self.limbs))
+            }
+            _ => BigInt::new(self.negative, sub_magnitudes(&self.limbs, &other.limbs)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let product = *a as u64 * *b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        BigInt::new(self.negative != other.negative, limbs)
+    }
+}
+
+impl BitAnd for &BigInt {
+    type Output = BigInt;
+
+    fn bitand(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BigInt {
+    type Output = BigInt;
+
+    fn bitor(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BigInt {
+    type Output = BigInt;
+
+    fn bitxor(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |a, b| a ^ b)
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.limbs, &other.limbs),
+            (true, true) => compare_magnitudes(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Peel off nine decimal digits at a time, least significant first.
+        let mut chunks = Vec::new();
+        let mut limbs = self.limbs.clone();
+        while !limbs.is_empty() {
+            let mut remainder = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let value = remainder << 32 | *limb as u64;
+                *limb = (value / 1_000_000_000) as u32;
+                remainder = value % 1_000_000_000;
+            }
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+            chunks.push(remainder);
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{}", first)?;
+        }
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut limbs = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        limbs.push(sum as u32);
+        carry = sum >> 32;
+    }
+    limbs.push(carry as u32);
+    limbs
+}
+
+/// Computes `a - b`, which requires `a >= b`.
+fn sub_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut limbs = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, limb) in a.iter().enumerate() {
+        let mut difference = *limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if difference < 0 {
+            difference += 1 << 32;
+            borrow = 1;
+        }
+        limbs.push(difference as u32);
+    }
+    limbs
+}
+
+fn shift_right_magnitude(limbs: &[u32], bits: u64) -> Vec<u32> {
+    let skip = usize::try_from(bits / 32).unwrap_or(usize::MAX);
+    let offset = bits % 32;
+    let limbs = limbs.get(skip..).unwrap_or(&[]);
+    (0..limbs.len())
+        .map(|i| {
+            let high = *limbs.get(i + 1).unwrap_or(&0) as u64;
+            ((high << 32 | limbs[i] as u64) >> offset) as u32
+        })
+        .collect()
+}
+
+/// Truncating division of magnitudes, one limb of the quotient at a time
+/// (Knuth's algorithm D, TAOCP 4.3.1). `b` must not be zero.
+fn divide_magnitudes(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if compare_magnitudes(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+
+    if let [divisor] = b {
+        let divisor = *divisor as u64;
+        let mut quotient = vec![0u32; a.len()];
+        let mut remainder = 0u64;
+        for (i, limb) in a.iter().enumerate().rev() {
+            let value = remainder << 32 | *limb as u64;
+            quotient[i] = (value / divisor) as u32;
+            remainder = value % divisor;
+        }
+        return (quotient, vec![remainder as u32]);
+    }
+
+    // Shift both so the divisor's top bit is set, which makes each estimate
+    // of a quotient limb at most two too large.
+    let shift = b[b.len() - 1].leading_zeros();
+    let n = b.len();
+    let b = &normalize(b, shift)[..n];
+    let mut a = normalize(a, shift);
+    let top = b[n - 1] as u64;
+    let next = b[n - 2] as u64;
+    let mut quotient = vec![0u32; a.len() - n];
+
+    for j in (0..quotient.len()).rev() {
+        // Estimate from the top two limbs, then correct with the third.
+        let numerator = (a[j + n] as u64) << 32 | a[j + n - 1] as u64;
+        let mut estimate = numerator / top;
+        let mut remainder = numerator % top;
+        while estimate > u32::MAX as u64
+            || estimate * next > (remainder << 32 | a[j + n - 2] as u64)
+        {
+            estimate -= 1;
+            remainder += top;
+            if remainder > u32::MAX as u64 {
+                break;
+            }
+        }
+
+        // a[j..=j + n] -= estimate * b
+        let mut carry = 0u64;
+        let mut borrow = 0i64;
+        for i in 0..n {
+            let product = estimate * b[i] as u64 + carry;
+            carry = product >> 32;
+            let difference = a[i + j] as i64 - (product as u32) as i64 - borrow;
+            a[i + j] = difference as u32;
+            borrow = (difference < 0) as i64;
+        }
+        let difference = a[j + n] as i64 - carry as i64 - borrow;
+        a[j + n] = difference as u32;
+
+        // Rarely the estimate is still one too large: add `b` back.
+        if difference < 0 {
+            estimate -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = a[i + j] as u64 + b[i] as u64 + carry;
+                a[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            a[j + n] = a[j + n].wrapping_add(carry as u32);
+        }
+        quotient[j] = estimate as u32;
+    }
+
+    (quotient, shift_right_magnitude(&a[..n], shift as u64))
+}
+
+/// Shifts `limbs` left by fewer than 32 bits, into one extra limb.
+fn normalize(limbs: &[u32], shift: u32) -> Vec<u32> {
+    let mut shifted = Vec::with_capacity(limbs.len() + 1);
+    let mut carry = 0u32;
+    for limb in limbs {
+        let wide = (*limb as u64) << shift;
+        shifted.push(wide as u32 | carry);
+        carry = (wide >> 32) as u32;
+    }
+    shifted.push(carry);
+    shifted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::Value;
+
+    /// Parses a signed decimal number.
+    fn big(digits: &str) -> BigInt {
+        match digits.strip_prefix('-') {
+            Some(magnitude) => -&BigInt::from_digits(magnitude, 10),
+            None => BigInt::from_digits(digits, 10),
+        }
+    }
+
+    #[test]
+    fn parses_digits_in_any_radix() {
+        assert_eq!(BigInt::from_digits("ff_ff", 16), BigInt::from(0xffff));
+        assert_eq!(
+            BigInt::from_digits("1_0000_0000", 16).to_string(),
+            "4294967296"
+        );
+        assert_eq!(
+            BigInt::from_digits("18446744073709551616", 10).to_string(),
+            "18446744073709551616"
+        );
+        assert!(BigInt::from_digits("000", 10).is_zero());
+    }
+
+    #[test]
+    fn displays_zero_chunks_inside_the_number() {
+        assert_eq!(
+            big("1000000000000000001").to_string(),
+            "1000000000000000001"
+        );
+        assert_eq!(
+            big("-1000000000000000000000000005").to_string(),
+            "-1000000000000000000000000005"
+        );
+        assert_eq!(big("-0").to_string(), "0");
+    }
+
+    #[test]
+    fn converts_at_the_i64_boundaries() {
+        assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+        assert_eq!(BigInt::from(i64::MAX).to_i64(), Some(i64::MAX));
+        assert_eq!(BigInt::from(i64::MIN).to_string(), "-9223372036854775808");
+        assert_eq!(big("9223372036854775808").to_i64(), None);
+        assert_eq!(big("-9223372036854775809").to_i64(), None);
+        assert_eq!(
+            big("-18446744073709551616").to_f64(),
+            -18446744073709551616.0
+        );
+    }
+
+    #[test]
+    fn carries_and_borrows_across_limbs() {
+        let limb = big("4294967295");
+        assert_eq!((&limb + &BigInt::from(1)).to_string(), "4294967296");
+        let max = big("18446744073709551615");
+        assert_eq!(
+            (&max + &BigInt::from(1)).to_string(),
+            "18446744073709551616"
+        );
+        assert_eq!((&big("18446744073709551616") - &BigInt::from(1)), max);
+        assert_eq!(
+            (&max * &max).to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(
+            (&BigInt::from(1) - &max).to_string(),
+            "-18446744073709551614"
+        );
+    }
+
+    #[test]
+    fn floor_division_follows_the_sign_of_the_divisor() {
+        let cases = [
+            ("7", "2", "3", "1"),
+            ("-7", "2", "-4", "1"),
+            ("7", "-2", "-4", "-1"),
+            ("-7", "-2", "3", "-1"),
+            ("-6", "3", "-2", "0"),
+            (
+                "1000000000000000000000000000007",
+                "10000000003",
+                "99999999970000000008",
+                "9999999983",
+            ),
+            (
+                "-1000000000000000000000000000007",
+                "10000000003",
+                "-99999999970000000009",
+                "20",
+            ),
+            (
+                "1000000000000000000000000000007",
+                "-10000000003",
+                "-99999999970000000009",
+                "-20",
+            ),
+            (
+                "-1000000000000000000000000000007",
+                "-10000000003",
+                "99999999970000000008",
+                "-9999999983",
+            ),
+        ];
+        for (a, b, quotient, remainder) in cases {
+            let (q, r) = big(a).div_mod_floor(&big(b)).unwrap();
+            assert_eq!(
+                (q.to_string(), r.to_string()),
+                (quotient.to_string(), remainder.to_string())
+            );
+        }
+        assert_eq!(big("5").div_mod_floor(&big("0")), None);
+    }
+
+    #[test]
+    fn bitwise_operations_use_twos_complement() {
+        let a = big("-1180591620717411291079");
+        let b = big("36893488151450271471");
+        assert_eq!((&a & &b).to_string(), "12329");
+        assert_eq!((&a | &b).to_string(), "-1143698132565961031937");
+        assert_eq!((&a ^ &b).to_string(), "-1143698132565961044266");
+        assert_eq!((&-&a & &-&b).to_string(), "1143698132565961031937");
+        assert_eq!(&BigInt::from(-1) & &b, b);
+    }
+
+    #[test]
+    fn values_shrink_back_to_integers_when_they_fit() {
+        assert!(matches!(
+            Value::from(big("9223372036854775807")),
+            Value::Integer(i64::MAX)
+        ));
+        assert!(matches!(
+            Value::from(big("-9223372036854775808")),
+            Value::Integer(i64::MIN)
+        ));
+        assert!(matches!(
+            Value::from(big("9223372036854775808")),
+            Value::BigInt(_)
+        ));
+        let huge = big("18446744073709551616");
+        assert!(matches!(Value::from(&huge - &huge), Value::Integer(0)));
+    }
+
+    #[test]
+    fn shifts_whole_limbs_and_the_bits_within_them() {
+        let number = big("-1180591620717411303429");
+        assert_eq!(
+            number.shift_left(40).to_string(),
+            "-1298074214633706907138121640443904"
+        );
+        assert_eq!(number.shift_right(3).to_string(), "-147573952589676412929");
+        assert_eq!(number.shift_right(64).to_string(), "-65");
+        assert_eq!(number.shift_right(1000).to_string(), "-1");
+        assert_eq!((-&number).shift_right(64).to_string(), "64");
+        assert_eq!(
+            BigInt::from(1).shift_left(64).to_string(),
+            "18446744073709551616"
+        );
+        assert_eq!(
+            big("-18446744073709551616").shift_right(64).to_string(),
+            "-1"
+        );
+        assert_eq!(BigInt::from(1).shift_left(100).bits(), 101);
+    }
+
+    #[test]
+    fn divides_multi_limb_numbers() {
+        let (quotient, remainder) = big("340282366920938463463374607431768211455")
+            .div_mod_floor(&big("18446744073709551617"))
+            .unwrap();
+        assert_eq!(quotient.to_string(), "18446744073709551615");
+        assert_eq!(remainder.to_string(), "0");
+
+        let (quotient, remainder) = big("123456789012345678901234567890123456789")
+            .div_mod_floor(&big("98765432109876543210"))
+            .unwrap();
+        assert_eq!(quotient.to_string(), "1249999988609375000");
+        assert_eq!(remainder.to_string(), "15297067891529706789");
+    }
+
+    #[test]
+    fn division_agrees_with_multiplication() {
+        // Limbs drawn mostly from the edges, where the quotient estimate and
+        // the add-back step go wrong if they are going to.
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            match state % 4 {
+                0 => 0,
+                1 => u32::MAX,
+                2 => 1 << 31,
+                _ => (state >> 32) as u32,
+            }
+        };
+        for _ in 0..2000 {
+            let a_len = (next() % 8) as usize;
+            let b_len = (next() % 5) as usize + 1;
+            let dividend = BigInt::new(false, (0..a_len).map(|_| next()).collect());
+            let mut divisor = BigInt::new(false, (0..b_len).map(|_| next()).collect());
+            if divisor.is_zero() {
+                divisor = BigInt::from(1);
+            }
+
+            let (quotient, remainder) = dividend.div_mod_floor(&divisor).unwrap();
+            assert_eq!(&(&quotient * &divisor) + &remainder, dividend);
+            assert!(remainder < divisor);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use crate::{error::RuntimeError, interpreter::Interpreter, value::Value};
+
+/// Anything that can appear on the left of a call expression.
+pub trait Callable {
+    fn arity(&self) -> usize;
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError>;
+}
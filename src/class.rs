@@ -0,0 +1,113 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
+
+use crate::{
+    callable::Callable, error::RuntimeError, function::LoxFunction, interpreter::Interpreter,
+    token::Token, value::Value,
+};
+
+#[derive(Debug)]
+pub struct LoxClass {
+    name: String,
+    superclass: Option<Rc<LoxClass>>,
+    methods: HashMap<String, Rc<LoxFunction>>,
+}
+
+impl LoxClass {
+    pub fn new(
+        name: String,
+        superclass: Option<Rc<LoxClass>>,
+        methods: HashMap<String, Rc<LoxFunction>>,
+    ) -> Self {
+        LoxClass {
+            name: name,
+            superclass: superclass,
+            methods: methods,
+        }
+    }
+
+    /// Looks `name` up on this class first and then along the superclass chain.
+    pub fn find_method(&self, name: &str) -> Option<Rc<LoxFunction>> {
+        if let Some(method) = self.methods.get(name) {
+            return Some(Rc::clone(method));
+        }
+
+        match &self.superclass {
+            Some(superclass) => superclass.find_method(name),
+            None => None,
+        }
+    }
+}
+
+/// Calling a class creates a new instance and runs its `init` method, if
+/// any. The instance has to point back at its class, which is why this is
+/// implemented on the `Rc` rather than on `LoxClass` itself.
+impl Callable for Rc<LoxClass> {
+    fn arity(&self) -> usize {
+        match self.find_method("init") {
+            Some(initializer) => initializer.arity(),
+            None => 0,
+        }
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let instance = Rc::new(RefCell::new(LoxInstance::new(Rc::clone(self))));
+        if let Some(initializer) = self.find_method("init") {
+            initializer
+                .bind(Rc::clone(&instance))
+                .call(interpreter, arguments)?;
+        }
+
+        Ok(Value::Instance(instance))
+    }
+}
+
+impl Display for LoxClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug)]
+pub struct LoxInstance {
+    class: Rc<LoxClass>,
+    fields: HashMap<String, Value>,
+}
+
+impl LoxInstance {
+    pub fn new(class: Rc<LoxClass>) -> Self {
+        LoxInstance {
+            class: class,
+            fields: HashMap::new(),
+        }
+    }
+
+    /// Fields shadow methods; methods come back bound to `instance`.
+    pub fn get(instance: &Rc<RefCell<LoxInstance>>, name: &Token) -> Result<Value, RuntimeError> {
+        let this = instance.borrow();
+        if let Some(value) = this.fields.get(&name.lexeme()) {
+            return Ok(value.clone());
+        }
+
+        match this.class.find_method(&name.lexeme()) {
+            Some(method) => Ok(Value::Function(Rc::new(method.bind(Rc::clone(instance))))),
+            None => Err(RuntimeError::new(
+                name.clone(),
+                format!("Undefined property '{}'.", name.lexeme()),
+            )),
+        }
+    }
+
+    pub fn set(&mut self, name: &Token, value: Value) {
+        self.fields.insert(name.lexeme(), value);
+    }
+}
+
+impl Display for LoxInstance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} instance", self.class.name)
+    }
+}
@@ -0,0 +1,195 @@
+use std::{env, io::IsTerminal};
+
+use crate::{
+    error::{Error, RuntimeError},
+    span::Span,
+};
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders errors together with the piece of source they point at:
+///
+/// ```text
+/// error: Expect ';' after value.
+///  --> script.lox:3:9
+///   |
+/// 3 | print 1 2;
+///   |         ^
+/// ```
+///
+/// Output is colored when stderr is a terminal and `NO_COLOR` is not set.
+pub struct Reporter {
+    file_name: String,
+    source: String,
+    color: bool,
+}
+
+impl Reporter {
+    pub fn new(file_name: String, source: String) -> Self {
+        let color = std::io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+        Reporter {
+            file_name: file_name,
+            source: source,
+            color: color,
+        }
+    }
+
+    pub fn error(&self, error: &Error) -> String {
+        self.render(&error.message(), error.line(), error.column(), error.span())
+    }
+
+    pub fn runtime_error(&self, error: &RuntimeError) -> String {
+        let token = error.token();
+        self.render(&error.message(), token.line(), token.column(), token.span())
+    }
+
+    fn render(&self, message: &str, line: usize, column: usize, span: Span) -> String {
+        let gutter = " ".repeat(line.to_string().len());
+        let mut output = format!(
+            "{}: {}\n{}{}-->{} {}:{}:{}\n",
+            self.paint(RED, "error"),
+            self.paint(BOLD, message),
+            gutter,
+            self.start(BLUE),
+            self.end(),
+            self.file_name,
+            line,
+            column
+        );
+
+        if let Some((text, padding, width)) = self.snippet(span) {
+            let bar = self.paint(BLUE, "|");
+            output.push_str(&format!("{} {}\n", gutter, bar));
+            output.push_str(&format!(
+                "{} {} {}\n",
+                self.paint(BLUE, &line.to_string()),
+                bar,
+                text
+            ));
+            output.push_str(&format!(
+                "{} {} {}{}",
+                gutter,
+                bar,
+                padding,
+                self.paint(RED, &"^".repeat(width))
+            ));
+        }
+
+        output
+    }
+
+    /// Returns the source line containing the start of `span`, the
+    /// whitespace needed to line up with it, and how many carets to draw.
+    /// Tabs are kept in the padding so the carets line up in any terminal.
+    fn snippet(&self, span: Span) -> Option<(&str, String, usize)> {
+        let start = span.start().min(self.source.len());
+        let line_start = self.source.get(..start)?.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[line_start..]
+            .find('\n')
+            .map_or(self.source.len(), |i| line_start + i);
+        let text = self.source[line_start..line_end].trim_end_matches('\r');
+
+        let padding = self.source[line_start..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let end = span.end().min(line_start + text.len()).max(start);
+        let width = self.source.get(start..end)?.chars().count().max(1);
+
+        Some((text, padding, width))
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        format!("{}{}{}", self.start(style), text, self.end())
+    }
+
+    fn start<'a>(&self, style: &'a str) -> &'a str {
+        if self.color {
+            style
+        } else {
+            ""
+        }
+    }
+
+    fn end(&self) -> &'static str {
+        if self.color {
+            RESET
+        } else {
+            ""
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::Parser, scanner::Scanner};
+
+    fn reporter(source: &str) -> Reporter {
+        Reporter {
+            file_name: "test.lox".to_string(),
+            source: source.to_string(),
+            color: false,
+        }
+    }
+
+    fn snippet(source: &str, start: usize, end: usize) -> Option<(String, String, usize)> {
+        let reporter = reporter(source);
+        let snippet = reporter.snippet(Span::new(start, end));
+        snippet.map(|(text, padding, width)| (text.to_string(), padding, width))
+    }
+
+    #[test]
+    fn carets_count_characters_not_bytes() {
+        let source = "print \"héllo 😀\";";
+        let end = source.len() - 1;
+        assert_eq!(
+            snippet(source, 6, end),
+            Some((source.to_string(), " ".repeat(6), 9))
+        );
+    }
+
+    #[test]
+    fn padding_keeps_tabs() {
+        assert_eq!(
+            snippet("\tvar é = \t1;", 11, 12),
+            Some(("\tvar é = \t1;".to_string(), "\t        \t".to_string(), 1))
+        );
+    }
+
+    #[test]
+    fn end_of_input_after_a_trailing_newline_is_an_empty_line() {
+        assert_eq!(
+            snippet("print 1\n", 8, 8),
+            Some((String::new(), String::new(), 1))
+        );
+    }
+
+    #[test]
+    fn spans_running_past_the_line_stop_at_its_end() {
+        assert_eq!(
+            snippet("print \"ab\r\ncd\";", 6, 15),
+            Some(("print \"ab".to_string(), " ".repeat(6), 3))
+        );
+    }
+
+    #[test]
+    fn renders_missing_semicolon_after_the_last_token() {
+        let source = "print 1\n";
+        let tokens = Scanner::new(source.to_string()).scan_tokens();
+        let errors = Parser::new(tokens).parse().unwrap_err();
+        assert_eq!(
+            reporter(source).error(&errors[0]),
+            concat!(
+                "error: Expect ';' after value.\n",
+                " --> test.lox:1:8\n",
+                "  |\n",
+                "1 | print 1\n",
+                "  |        ^",
+            )
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use crate::{
+    error::Error,
+    expression::{
+        Assign, Binary, Call, Conditional, Expression, Get, Grouping, Interpolation, LiteralExp,
+        Logical, Set, Super, This, Unary, Update, Variable,
+    },
+    json::Json,
+    parser::Parser,
+    scanner::Scanner,
+    span::Span,
+    statement::{Block, Class, ExpressionStmt, Function, If, Print, Return, Stmt, Var, While},
+    token::{Literal, Token},
+    visitor::{StmtVisitor, Visitor},
+};
+
+/// Scans and parses `source` and describes the result as JSON:
+///
+/// ```text
+/// {"tokens": [...], "ast": [...], "errors": [...]}
+/// ```
+///
+/// `ast` is `null` when the source has lexical or syntax errors. Nothing is
+/// resolved or run, so only those two kinds of errors are reported.
+pub fn dump(source: &str) -> Json {
+    let mut scanner = Scanner::new(source.to_string());
+    let tokens = scanner.scan_tokens();
+    let mut errors: Vec<Json> = scanner.errors().iter().map(error_json).collect();
+
+    let ast = match Parser::new(tokens.clone()).parse() {
+        Ok(statements) if errors.is_empty() => {
+            Json::Array(statements.iter().map(statement_json).collect())
+        }
+        Ok(_) => Json::Null,
+        Err(parse_errors) => {
+            errors.extend(parse_errors.iter().map(error_json));
+            Json::Null
+        }
+    };
+
+    Json::object(vec![
+        (
+            "tokens",
+            Json::Array(tokens.iter().map(token_json).collect()),
+        ),
+        ("ast", ast),
+        ("errors", Json::Array(errors)),
+    ])
+}
+
+pub fn token_json(token: &Token) -> Json {
+    Json::object(vec![
+        // The variant name, so every type is spelled like `LEFT_PAREN`.
+        ("type", Json::string(&format!("{:?}", token.token_type()))),
+        ("lexeme", Json::string(&token.lexeme())),
+        ("literal", literal_json(token.literal().as_ref())),
+        ("line", Json::number(token.line())),
+        ("column", Json::number(token.column())),
+        ("span", span_json(token.span())),
+    ])
+}
+
+pub fn error_json(error: &Error) -> Json {
+    Json::object(vec![
+        ("message", Json::string(&error.message())),
+        ("line", Json::number(error.line())),
+        ("column", Json::number(error.column())),
+        ("span", span_json(error.span())),
+    ])
+}
+
+/// Every node has a `kind`, a `span` and its sub-nodes in `children`, in
+/// source order. Names and operators are extra string fields on the node.
+pub fn statement_json(statement: &Stmt) -> Json {
+    statement.accept(&mut JsonBuilder)
+}
+
+pub fn expression_json(expression: &Expression) -> Json {
+    expression.accept(&mut JsonBuilder)
+}
+
+fn node(kind: &str, span: Span, fields: Vec<(&str, Json)>, children: Vec<Json>) -> Json {
+    let mut entries = vec![("kind", Json::string(kind)), ("span", span_json(span))];
+    entries.extend(fields);
+    entries.push(("children", Json::Array(children)));
+    Json::object(entries)
+}
+
+fn span_json(span: Span) -> Json {
+    Json::object(vec![
+        ("start", Json::number(span.start())),
+        ("end", Json::number(span.end())),
+    ])
+}
+
+fn literal_json(literal: Option<&Literal>) -> Json {
+    match literal {
+        Some(Literal::String(string)) => Json::string(string),
+        Some(Literal::Integer(integer)) => Json::number(integer),
+        Some(Literal::BigInt(integer)) => Json::number(integer),
+        Some(Literal::Float(float)) if float.is_finite() => Json::number(format!("{:?}", float)),
+        Some(Literal::Float(_)) | None => Json::Null,
+    }
+}
+
+struct JsonBuilder;
+
+impl JsonBuilder {
+    fn statements(&mut self, statements: &[Stmt]) -> Vec<Json> {
+        statements
+            .iter()
+            .map(|statement| statement.accept(self))
+            .collect()
+    }
+
+    fn named(&mut self, kind: &str, span: Span, name: &Token, children: &[&Expression]) -> Json {
+        node(
+            kind,
+            span,
+            vec![("name", Json::string(&name.lexeme()))],
+            children.iter().map(|child| child.accept(self)).collect(),
+        )
+    }
+
+    fn operator(
+        &mut self,
+        kind: &str,
+        span: Span,
+        operator: &Token,
+        children: &[&Expression],
+    ) -> Json {
+        node(
+            kind,
+            span,
+            vec![("operator", Json::string(&operator.lexeme()))],
+            children.iter().map(|child| child.accept(self)).collect(),
+        )
+    }
+}
+
+impl StmtVisitor<Json> for JsonBuilder {
+    fn visit_expression_stmt(&mut self, statement: &ExpressionStmt) -> Json {
+        let children = vec![statement.expression().accept(self)];
+        node("Expression", statement.span(), vec![], children)
+    }
+
+    fn visit_print(&mut self, statement: &Print) -> Json {
+        let children = vec![statement.expression().accept(self)];
+        node("Print", statement.span(), vec![], children)
+    }
+
+    fn visit_var(&mut self, statement: &Var) -> Json {
+        let children = statement.initializer().into_iter().collect::<Vec<_>>();
+        self.named("Var", statement.span(), statement.name(), &children)
+    }
+
+    fn visit_block(&mut self, block: &Block) -> Json {
+        let children = self.statements(block.statements());
+        node("Block", block.span(), vec![], children)
+    }
+
+    fn visit_if(&mut self, statement: &If) -> Json {
+        let mut children = vec![
+            statement.condition().accept(self),
+            statement.then_branch().accept(self),
+        ];
+        if let Some(else_branch) = statement.else_branch() {
+            children.push(else_branch.accept(self));
+        }
+        node("If", statement.span(), vec![], children)
+    }
+
+    fn visit_while(&mut self, statement: &While) -> Json {
+        let children = vec![
+            statement.condition().accept(self),
+            statement.body().accept(self),
+        ];
+        node("While", statement.span(), vec![], children)
+    }
+
+    fn visit_function(&mut self, function: &Function) -> Json {
+        let params = function
+            .params()
+            .iter()
+            .map(|param| Json::string(&param.lexeme()))
+            .collect();
+        node(
+            "Function",
+            function.span(),
+            vec![
+                ("name", Json::string(&function.name().lexeme())),
+                ("params", Json::Array(params)),
+            ],
+            self.statements(function.body()),
+        )
+    }
+
+    fn visit_return(&mut self, statement: &Return) -> Json {
+        let children = match statement.value() {
+            Some(value) => vec![value.accept(self)],
+            None => vec![],
+        };
+        node("Return", statement.span(), vec![], children)
+    }
+
+    fn visit_class(&mut self, class: &Class) -> Json {
+        let superclass = class.superclass().map_or(Json::Null, |superclass| {
+            Json::string(&superclass.name().lexeme())
+        });
+        let methods = class
+            .methods()
+            .iter()
+            .map(|method| self.visit_function(method))
+            .collect();
+        node(
+            "Class",
+            class.span(),
+            vec![
+                ("name", Json::string(&class.name().lexeme())),
+                ("superclass", superclass),
+            ],
+            methods,
+        )
+    }
+}
+
+impl Visitor<Json> for JsonBuilder {
+    fn visit_literal(&mut self, literal: &LiteralExp) -> Json {
+        let token = literal.token();
+        let value = match token.literal() {
+            Some(literal) => literal_json(Some(&literal)),
+            None => match token.lexeme().as_str() {
+                "true" => Json::Bool(true),
+                "false" => Json::Bool(false),
+                _ => Json::Null,
+            },
+        };
+        node("Literal", literal.span(), vec![("value", value)], vec![])
+    }
+
+    fn visit_unary(&mut self, unary: &Unary) -> Json {
+        self.operator(
+            "Unary",
+            unary.span(),
+            unary.operator(),
+            &[unary.expression()],
+        )
+    }
+
+    fn visit_binary(&mut self, binary: &Binary) -> Json {
+        self.operator(
+            "Binary",
+            binary.span(),
+            binary.operator().token(),
+            &[binary.left(), binary.right()],
+        )
+    }
+
+    fn visit_grouping(&mut self, grouping: &Grouping) -> Json {
+        let children = vec![grouping.expression().accept(self)];
+        node("Grouping", grouping.span(), vec![], children)
+    }
+
+    fn visit_variable(&mut self, variable: &Variable) -> Json {
+        self.named("Variable", variable.span(), variable.name(), &[])
+    }
+
+    fn visit_assign(&mut self, assign: &Assign) -> Json {
+        self.named("Assign", assign.span(), assign.name(), &[assign.value()])
+    }
+
+    fn visit_logical(&mut self, logical: &Logical) -> Json {
+        self.operator(
+            "Logical",
+            logical.span(),
+            logical.operator(),
+            &[logical.left(), logical.right()],
+        )
+    }
+
+    fn visit_conditional(&mut self, conditional: &Conditional) -> Json {
+        let children = vec![
+            conditional.condition().accept(self),
+            conditional.then_branch().accept(self),
+            conditional.else_branch().accept(self),
+        ];
+        node("Conditional", conditional.span(), vec![], children)
+    }
+
+    fn visit_call(&mut self, call: &Call) -> Json {
+        let mut children = vec![call.callee().accept(self)];
+        for argument in call.arguments() {
+            children.push(argument.accept(self));
+        }
+        node("Call", call.span(), vec![], children)
+    }
+
+    fn visit_this(&mut self, this: &This) -> Json {
+        node("This", this.span(), vec![], vec![])
+    }
+
+    fn visit_get(&mut self, get: &Get) -> Json {
+        self.named("Get", get.span(), get.name(), &[get.object()])
+    }
+
+    fn visit_set(&mut self, set: &Set) -> Json {
+        self.named("Set", set.span(), set.name(), &[set.object(), set.value()])
+    }
+
+    fn visit_super(&mut self, expression: &Super) -> Json {
+        let method = Json::string(&expression.method().lexeme());
+        node("Super", expression.span(), vec![("method", method)], vec![])
+    }
+
+    fn visit_interpolation(&mut self, interpolation: &Interpolation) -> Json {
+        let children = interpolation
+            .parts()
+            .iter()
+            .map(|part| part.accept(self))
+            .collect();
+        node("Interpolation", interpolation.span(), vec![], children)
+    }
+
+    fn visit_update(&mut self, update: &Update) -> Json {
+        let mut children = vec![update.target().accept(self)];
+        children.extend(update.value().map(|value| value.accept(self)));
+        node(
+            "Update",
+            update.span(),
+            vec![
+                ("operator", Json::string(&update.operator().lexeme())),
+                ("postfix", Json::Bool(update.is_postfix())),
+            ],
+            children,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dumps_tokens_and_ast() {
+        assert_eq!(
+            dump("-x;").to_string(),
+            concat!(
+                r#"{"tokens":["#,
+                r#"{"type":"MINUS","lexeme":"-","literal":null,"line":1,"column":1,"span":{"start":0,"end":1}},"#,
+                r#"{"type":"IDENTIFIER","lexeme":"x","literal":null,"line":1,"column":2,"span":{"start":1,"end":2}},"#,
+                r#"{"type":"SEMICOLON","lexeme":";","literal":null,"line":1,"column":3,"span":{"start":2,"end":3}},"#,
+                r#"{"type":"EOF","lexeme":"","literal":null,"line":1,"column":4,"span":{"start":3,"end":3}}],"#,
+                r#""ast":[{"kind":"Expression","span":{"start":0,"end":3},"children":["#,
+                r#"{"kind":"Unary","span":{"start":0,"end":2},"operator":"-","children":["#,
+                r#"{"kind":"Variable","span":{"start":1,"end":2},"name":"x","children":[]}]}]}],"#,
+                r#""errors":[]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn reports_errors_without_an_ast() {
+        let json = dump("print \"a\n\" +;").to_string();
+        assert!(json.contains(r#""lexeme":"\"a\n\"""#));
+        assert!(json.contains(r#""ast":null"#));
+        assert!(json.contains(r#""message":"No primary expression matched.""#));
+    }
+
+    #[test]
+    fn token_types_are_variant_names() {
+        let types: Vec<String> = match dump("({ != ~/").get("tokens") {
+            Some(Json::Array(tokens)) => tokens
+                .iter()
+                .map(|token| token.get("type").expect("token should have a type"))
+                .map(|token_type| token_type.to_string())
+                .collect(),
+            _ => panic!("dump should have a tokens array"),
+        };
+        assert_eq!(
+            types,
+            [
+                r#""LEFT_PAREN""#,
+                r#""LEFT_BRACE""#,
+                r#""BANG_EQUAL""#,
+                r#""TILDE_SLASH""#,
+                r#""EOF""#
+            ]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::{error::RuntimeError, token::Token, value::Value};
+
+/// A single scope of variable bindings. Scopes are chained through
+/// `enclosing` so that lookups walk outwards until the global scope.
+#[derive(Debug)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+    enclosing: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Environment {
+            values: HashMap::new(),
+            enclosing: None,
+        }
+    }
+
+    pub fn with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Self {
+        Environment {
+            values: HashMap::new(),
+            enclosing: Some(enclosing),
+        }
+    }
+
+    pub fn define(&mut self, name: String, value: Value) {
+        self.values.insert(name, value);
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Value, RuntimeError> {
+        if let Some(value) = self.values.get(&name.lexeme()) {
+            return Ok(value.clone());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => Err(Self::undefined(name)),
+        }
+    }
+
+    pub fn assign(&mut self, name: &Token, value: Value) -> Result<(), RuntimeError> {
+        if let Some(slot) = self.values.get_mut(&name.lexeme()) {
+            *slot = value;
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(Self::undefined(name)),
+        }
+    }
+
+    /// Reads a variable exactly `distance` scopes up the chain, as computed by
+    /// the resolver.
+    pub fn get_at(&self, distance: usize, name: &Token) -> Result<Value, RuntimeError> {
+        self.lookup_at(distance, &name.lexeme())
+            .ok_or_else(|| Self::undefined(name))
+    }
+
+    pub fn lookup_at(&self, distance: usize, name: &str) -> Option<Value> {
+        if distance == 0 {
+            return self.values.get(name).cloned();
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().lookup_at(distance - 1, name),
+            None => None,
+        }
+    }
+
+    pub fn assign_at(
+        &mut self,
+        distance: usize,
+        name: &Token,
+        value: Value,
+    ) -> Result<(), RuntimeError> {
+        if distance == 0 {
+            self.values.insert(name.lexeme(), value);
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign_at(distance - 1, name, value),
+            None => Err(Self::undefined(name)),
+        }
+    }
+
+    fn undefined(name: &Token) -> RuntimeError {
+        RuntimeError::new(
+            name.clone(),
+            format!("Undefined variable '{}'.", name.lexeme()),
+        )
+    }
+}
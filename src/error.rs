@@ -1,32 +1,68 @@
 use std::error::Error as StdError;
 use std::fmt;
 
+use crate::{span::Span, token::Token};
+
+/// A static error found while scanning, parsing or resolving, located by
+/// line, column and the span of source it refers to.
 #[derive(Debug)]
 pub struct Error {
     line: usize,
+    column: usize,
+    span: Span,
     message: String,
 }
 
 impl Error {
-    pub fn new(line: usize, message: String) -> Self {
+    pub fn new(line: usize, column: usize, span: Span, message: String) -> Self {
         Error {
             line: line,
+            column: column,
+            span: span,
             message: message,
         }
     }
 
+    /// An error pointing at `token`.
+    pub fn at(token: &Token, message: String) -> Self {
+        Error::new(token.line(), token.column(), token.span(), message)
+    }
+
     pub fn line(&self) -> usize {
         self.line
     }
 
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
     pub fn message(&self) -> String {
         self.message.clone()
     }
+
+    /// Each error as (line, column, message), which is what tests compare.
+    #[cfg(test)]
+    pub fn summarize(errors: &[Error]) -> Vec<(usize, usize, String)> {
+        errors
+            .iter()
+            .map(|error| (error.line(), error.column(), error.message()))
+            .collect()
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "ERROR at {}: {})", self.line(), self.message)
+        write!(
+            f,
+            "[line {}:{}] Error: {}",
+            self.line(),
+            self.column(),
+            self.message()
+        )
     }
 }
 impl StdError for Error {
@@ -34,3 +70,47 @@ impl StdError for Error {
         &self.message[..]
     }
 }
+
+/// An error raised while evaluating code that scanned and parsed correctly,
+/// such as applying `-` to a string. It keeps the token of the offending
+/// operator so the report can point at the right line.
+#[derive(Debug)]
+pub struct RuntimeError {
+    token: Token,
+    message: String,
+}
+
+impl RuntimeError {
+    pub fn new(token: Token, message: String) -> Self {
+        RuntimeError {
+            token: token,
+            message: message,
+        }
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}\n[line {}:{}]",
+            self.message(),
+            self.token().line(),
+            self.token().column()
+        )
+    }
+}
+
+impl StdError for RuntimeError {
+    fn description(&self) -> &str {
+        &self.message[..]
+    }
+}
@@ -1,6 +1,12 @@
-use std::fmt::Display;
+use std::cell::Cell;
+
+use crate::{
+    span::Span,
+    token::Token,
+    token_type::TokenType,
+    visitor::{MutVisitor, Visitor},
+};
 
-use crate::{token::Token, token_type::TokenType};
 #[derive(Debug)]
 pub struct Unary {
     left: Token,
@@ -22,12 +28,34 @@ impl Unary {
                     expression: Box::new(expression),
                 }
             }
+            "~" => {
+                return Unary {
+                    left: operator,
+                    expression: Box::new(expression),
+                }
+            }
             _ => {
                 eprintln!("ERROR: invalid left operator for unary expression.");
                 panic!();
             }
         }
     }
+
+    pub fn operator(&self) -> &Token {
+        &self.left
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    pub fn expression_mut(&mut self) -> &mut Expression {
+        &mut self.expression
+    }
+
+    pub fn span(&self) -> Span {
+        self.left.span().to(self.expression.span())
+    }
 }
 #[derive(Debug)]
 pub struct Binary {
@@ -44,23 +72,58 @@ impl Binary {
             right: Box::new(right),
         }
     }
+
+    pub fn left(&self) -> &Expression {
+        &self.left
+    }
+
+    pub fn left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+
+    pub fn operator(&self) -> &Operator {
+        &self.operator
+    }
+
+    pub fn right(&self) -> &Expression {
+        &self.right
+    }
+
+    pub fn right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
+
+    pub fn span(&self) -> Span {
+        self.left.span().to(self.right.span())
+    }
 }
 
 #[derive(Debug)]
 pub struct Grouping {
-    left_brace: char,
     expression: Box<Expression>,
-    rigth_brace: char,
+    span: Span,
 }
 
 impl Grouping {
-    pub fn new(expression: Expression) -> Self {
+    /// `span` covers both parentheses, which are not kept in the tree.
+    pub fn new(expression: Expression, span: Span) -> Self {
         Grouping {
-            left_brace: '(',
             expression: Box::new(expression),
-            rigth_brace: ')',
+            span: span,
         }
     }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    pub fn expression_mut(&mut self) -> &mut Expression {
+        &mut self.expression
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
 #[derive(Debug)]
@@ -69,23 +132,18 @@ pub struct Operator {
 }
 
 impl Operator {
+    /// `Parser::rule` decides which tokens are binary operators, so the token
+    /// is taken as it is.
     pub fn new(operator: Token) -> Self {
-        match &operator.lexeme()[..] {
-            "==" => return Operator { token: operator },
-            "!=" => return Operator { token: operator },
-            "<" => return Operator { token: operator },
-            "<=" => return Operator { token: operator },
-            ">" => return Operator { token: operator },
-            ">=" => return Operator { token: operator },
-            "+" => return Operator { token: operator },
-            "-" => return Operator { token: operator },
-            "*" => return Operator { token: operator },
-            "/" => return Operator { token: operator },
-            _ => {
-                eprintln!("ERROR: invalid operator");
-                panic!();
-            }
-        }
+        Operator { token: operator }
+    }
+
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    pub fn span(&self) -> Span {
+        self.token.span()
     }
 }
 
@@ -107,6 +165,11 @@ impl LiteralExp {
                     literal_expression: token,
                 }
             }
+            TokenType::INTERPOLATION => {
+                return LiteralExp {
+                    literal_expression: token,
+                }
+            }
             TokenType::TRUE => {
                 return LiteralExp {
                     literal_expression: token,
@@ -122,12 +185,489 @@ impl LiteralExp {
                     literal_expression: token,
                 }
             }
+            // Stands in for a bad lexeme so parsing can go on past it.
+            TokenType::ERROR => {
+                return LiteralExp {
+                    literal_expression: token,
+                }
+            }
             _ => {
                 eprintln!("ERROR invalid token type for a literal");
                 panic!();
             }
         }
     }
+
+    pub fn token(&self) -> &Token {
+        &self.literal_expression
+    }
+
+    pub fn span(&self) -> Span {
+        self.literal_expression.span()
+    }
+}
+
+#[derive(Debug)]
+pub struct Variable {
+    name: Token,
+    depth: Cell<Option<usize>>,
+}
+
+impl Variable {
+    pub fn new(name: Token) -> Self {
+        Variable {
+            name: name,
+            depth: Cell::new(None),
+        }
+    }
+
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    /// Number of scopes between the use and the declaration, as computed by
+    /// the resolver. `None` means the variable is global.
+    pub fn depth(&self) -> Option<usize> {
+        self.depth.get()
+    }
+
+    pub fn resolve(&self, depth: usize) {
+        self.depth.set(Some(depth));
+    }
+
+    pub fn span(&self) -> Span {
+        self.name.span()
+    }
+}
+
+#[derive(Debug)]
+pub struct Assign {
+    name: Token,
+    value: Box<Expression>,
+    depth: Cell<Option<usize>>,
+}
+
+impl Assign {
+    pub fn new(name: Token, value: Expression) -> Self {
+        Assign {
+            name: name,
+            value: Box::new(value),
+            depth: Cell::new(None),
+        }
+    }
+
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    pub fn depth(&self) -> Option<usize> {
+        self.depth.get()
+    }
+
+    pub fn resolve(&self, depth: usize) {
+        self.depth.set(Some(depth));
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+
+    pub fn span(&self) -> Span {
+        self.name.span().to(self.value.span())
+    }
+}
+
+#[derive(Debug)]
+pub struct Logical {
+    left: Box<Expression>,
+    operator: Token,
+    right: Box<Expression>,
+}
+
+impl Logical {
+    pub fn new(left: Expression, operator: Token, right: Expression) -> Self {
+        Logical {
+            left: Box::new(left),
+            operator: operator,
+            right: Box::new(right),
+        }
+    }
+
+    pub fn left(&self) -> &Expression {
+        &self.left
+    }
+
+    pub fn left_mut(&mut self) -> &mut Expression {
+        &mut self.left
+    }
+
+    pub fn operator(&self) -> &Token {
+        &self.operator
+    }
+
+    pub fn right(&self) -> &Expression {
+        &self.right
+    }
+
+    pub fn right_mut(&mut self) -> &mut Expression {
+        &mut self.right
+    }
+
+    pub fn span(&self) -> Span {
+        self.left.span().to(self.right.span())
+    }
+}
+
+/// `condition ? then_branch : else_branch`. Only the chosen branch is evaluated.
+#[derive(Debug)]
+pub struct Conditional {
+    condition: Box<Expression>,
+    then_branch: Box<Expression>,
+    else_branch: Box<Expression>,
+}
+
+impl Conditional {
+    pub fn new(condition: Expression, then_branch: Expression, else_branch: Expression) -> Self {
+        Conditional {
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch: Box::new(else_branch),
+        }
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn condition_mut(&mut self) -> &mut Expression {
+        &mut self.condition
+    }
+
+    pub fn then_branch(&self) -> &Expression {
+        &self.then_branch
+    }
+
+    pub fn then_branch_mut(&mut self) -> &mut Expression {
+        &mut self.then_branch
+    }
+
+    pub fn else_branch(&self) -> &Expression {
+        &self.else_branch
+    }
+
+    pub fn else_branch_mut(&mut self) -> &mut Expression {
+        &mut self.else_branch
+    }
+
+    pub fn span(&self) -> Span {
+        self.condition.span().to(self.else_branch.span())
+    }
+}
+
+#[derive(Debug)]
+pub struct Call {
+    callee: Box<Expression>,
+    paren: Token,
+    arguments: Vec<Expression>,
+}
+
+impl Call {
+    pub fn new(callee: Expression, paren: Token, arguments: Vec<Expression>) -> Self {
+        Call {
+            callee: Box::new(callee),
+            paren: paren,
+            arguments: arguments,
+        }
+    }
+
+    pub fn callee(&self) -> &Expression {
+        &self.callee
+    }
+
+    pub fn callee_mut(&mut self) -> &mut Expression {
+        &mut self.callee
+    }
+
+    /// The closing parenthesis, used to report errors at the call site.
+    pub fn paren(&self) -> &Token {
+        &self.paren
+    }
+
+    pub fn arguments(&self) -> &[Expression] {
+        &self.arguments
+    }
+
+    pub fn arguments_mut(&mut self) -> &mut [Expression] {
+        &mut self.arguments
+    }
+
+    pub fn span(&self) -> Span {
+        self.callee.span().to(self.paren.span())
+    }
+}
+
+#[derive(Debug)]
+pub struct This {
+    keyword: Token,
+    depth: Cell<Option<usize>>,
+}
+
+impl This {
+    pub fn new(keyword: Token) -> Self {
+        This {
+            keyword: keyword,
+            depth: Cell::new(None),
+        }
+    }
+
+    pub fn keyword(&self) -> &Token {
+        &self.keyword
+    }
+
+    pub fn depth(&self) -> Option<usize> {
+        self.depth.get()
+    }
+
+    pub fn resolve(&self, depth: usize) {
+        self.depth.set(Some(depth));
+    }
+
+    pub fn span(&self) -> Span {
+        self.keyword.span()
+    }
+}
+
+#[derive(Debug)]
+pub struct Get {
+    object: Box<Expression>,
+    name: Token,
+}
+
+impl Get {
+    pub fn new(object: Expression, name: Token) -> Self {
+        Get {
+            object: Box::new(object),
+            name: name,
+        }
+    }
+
+    pub fn object(&self) -> &Expression {
+        &self.object
+    }
+
+    pub fn object_mut(&mut self) -> &mut Expression {
+        &mut self.object
+    }
+
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    /// Used by the parser to turn a property access into an assignment target.
+    pub fn into_parts(self) -> (Expression, Token) {
+        (*self.object, self.name)
+    }
+
+    pub fn span(&self) -> Span {
+        self.object.span().to(self.name.span())
+    }
+}
+
+#[derive(Debug)]
+pub struct Set {
+    object: Box<Expression>,
+    name: Token,
+    value: Box<Expression>,
+}
+
+impl Set {
+    pub fn new(object: Expression, name: Token, value: Expression) -> Self {
+        Set {
+            object: Box::new(object),
+            name: name,
+            value: Box::new(value),
+        }
+    }
+
+    pub fn object(&self) -> &Expression {
+        &self.object
+    }
+
+    pub fn object_mut(&mut self) -> &mut Expression {
+        &mut self.object
+    }
+
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut Expression {
+        &mut self.value
+    }
+
+    pub fn span(&self) -> Span {
+        self.object.span().to(self.value.span())
+    }
+}
+
+/// Compound assignment (`a.b += 2`) or increment/decrement (`++i`, `i--`) of
+/// a variable or field. The target is a `Variable` or `Get` and is evaluated
+/// only once. Without a value the target is stepped by one.
+#[derive(Debug)]
+pub struct Update {
+    target: Box<Expression>,
+    operator: Token,
+    binary: TokenType,
+    value: Option<Box<Expression>>,
+    postfix: bool,
+}
+
+impl Update {
+    pub fn compound(
+        target: Expression,
+        operator: Token,
+        binary: TokenType,
+        value: Expression,
+    ) -> Self {
+        Update {
+            target: Box::new(target),
+            operator: operator,
+            binary: binary,
+            value: Some(Box::new(value)),
+            postfix: false,
+        }
+    }
+
+    pub fn prefix(operator: Token, binary: TokenType, target: Expression) -> Self {
+        Update {
+            target: Box::new(target),
+            operator: operator,
+            binary: binary,
+            value: None,
+            postfix: false,
+        }
+    }
+
+    pub fn postfix(target: Expression, operator: Token, binary: TokenType) -> Self {
+        Update {
+            target: Box::new(target),
+            operator: operator,
+            binary: binary,
+            value: None,
+            postfix: true,
+        }
+    }
+
+    pub fn target(&self) -> &Expression {
+        &self.target
+    }
+
+    pub fn target_mut(&mut self) -> &mut Expression {
+        &mut self.target
+    }
+
+    pub fn operator(&self) -> &Token {
+        &self.operator
+    }
+
+    pub fn value(&self) -> Option<&Expression> {
+        self.value.as_deref()
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut Expression> {
+        self.value.as_deref_mut()
+    }
+
+    /// Whether the expression yields the target's value from before the update.
+    pub fn is_postfix(&self) -> bool {
+        self.postfix
+    }
+
+    /// The binary operator applied to the target, e.g. `+` for `+=` and `++`.
+    pub fn binary_operator(&self) -> TokenType {
+        self.binary
+    }
+
+    pub fn span(&self) -> Span {
+        match &self.value {
+            Some(value) => self.target.span().to(value.span()),
+            None => self.target.span().to(self.operator.span()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Super {
+    keyword: Token,
+    method: Token,
+    depth: Cell<Option<usize>>,
+}
+
+impl Super {
+    pub fn new(keyword: Token, method: Token) -> Self {
+        Super {
+            keyword: keyword,
+            method: method,
+            depth: Cell::new(None),
+        }
+    }
+
+    pub fn keyword(&self) -> &Token {
+        &self.keyword
+    }
+
+    pub fn method(&self) -> &Token {
+        &self.method
+    }
+
+    pub fn depth(&self) -> Option<usize> {
+        self.depth.get()
+    }
+
+    pub fn resolve(&self, depth: usize) {
+        self.depth.set(Some(depth));
+    }
+
+    pub fn span(&self) -> Span {
+        self.keyword.span().to(self.method.span())
+    }
+}
+
+/// A string literal with embedded expressions, such as `"a ${b} c"`.
+/// `parts` alternates between string literals and the embedded expressions.
+#[derive(Debug)]
+pub struct Interpolation {
+    parts: Vec<Expression>,
+    span: Span,
+}
+
+impl Interpolation {
+    pub fn new(parts: Vec<Expression>, span: Span) -> Self {
+        Interpolation {
+            parts: parts,
+            span: span,
+        }
+    }
+
+    pub fn parts(&self) -> &[Expression] {
+        &self.parts
+    }
+
+    pub fn parts_mut(&mut self) -> &mut [Expression] {
+        &mut self.parts
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
 #[derive(Debug)]
@@ -136,5 +676,79 @@ pub enum Expression {
     Unary(Unary),
     Binary(Binary),
     Grouping(Grouping),
-    Operator(Operator),
+    Variable(Variable),
+    Assign(Assign),
+    Logical(Logical),
+    Conditional(Conditional),
+    Call(Call),
+    This(This),
+    Get(Get),
+    Set(Set),
+    Super(Super),
+    Interpolation(Interpolation),
+    Update(Update),
+}
+
+impl Expression {
+    /// Calls the `visitor` method matching this node's kind.
+    pub fn accept<R, V: Visitor<R> + ?Sized>(&self, visitor: &mut V) -> R {
+        match self {
+            Expression::LiteralExp(expression) => visitor.visit_literal(expression),
+            Expression::Unary(expression) => visitor.visit_unary(expression),
+            Expression::Binary(expression) => visitor.visit_binary(expression),
+            Expression::Grouping(expression) => visitor.visit_grouping(expression),
+            Expression::Variable(expression) => visitor.visit_variable(expression),
+            Expression::Assign(expression) => visitor.visit_assign(expression),
+            Expression::Logical(expression) => visitor.visit_logical(expression),
+            Expression::Conditional(expression) => visitor.visit_conditional(expression),
+            Expression::Call(expression) => visitor.visit_call(expression),
+            Expression::This(expression) => visitor.visit_this(expression),
+            Expression::Get(expression) => visitor.visit_get(expression),
+            Expression::Set(expression) => visitor.visit_set(expression),
+            Expression::Super(expression) => visitor.visit_super(expression),
+            Expression::Interpolation(expression) => visitor.visit_interpolation(expression),
+            Expression::Update(expression) => visitor.visit_update(expression),
+        }
+    }
+
+    /// Like `accept`, but lets the visitor modify the node.
+    pub fn accept_mut<R, V: MutVisitor<R> + ?Sized>(&mut self, visitor: &mut V) -> R {
+        match self {
+            Expression::LiteralExp(expression) => visitor.visit_literal(expression),
+            Expression::Unary(expression) => visitor.visit_unary(expression),
+            Expression::Binary(expression) => visitor.visit_binary(expression),
+            Expression::Grouping(expression) => visitor.visit_grouping(expression),
+            Expression::Variable(expression) => visitor.visit_variable(expression),
+            Expression::Assign(expression) => visitor.visit_assign(expression),
+            Expression::Logical(expression) => visitor.visit_logical(expression),
+            Expression::Conditional(expression) => visitor.visit_conditional(expression),
+            Expression::Call(expression) => visitor.visit_call(expression),
+            Expression::This(expression) => visitor.visit_this(expression),
+            Expression::Get(expression) => visitor.visit_get(expression),
+            Expression::Set(expression) => visitor.visit_set(expression),
+            Expression::Super(expression) => visitor.visit_super(expression),
+            Expression::Interpolation(expression) => visitor.visit_interpolation(expression),
+            Expression::Update(expression) => visitor.visit_update(expression),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::LiteralExp(expression) => expression.span(),
+            Expression::Unary(expression) => expression.span(),
+            Expression::Binary(expression) => expression.span(),
+            Expression::Grouping(expression) => expression.span(),
+            Expression::Variable(expression) => expression.span(),
+            Expression::Assign(expression) => expression.span(),
+            Expression::Logical(expression) => expression.span(),
+            Expression::Conditional(expression) => expression.span(),
+            Expression::Call(expression) => expression.span(),
+            Expression::This(expression) => expression.span(),
+            Expression::Get(expression) => expression.span(),
+            Expression::Set(expression) => expression.span(),
+            Expression::Super(expression) => expression.span(),
+            Expression::Interpolation(expression) => expression.span(),
+            Expression::Update(expression) => expression.span(),
+        }
+    }
 }
@@ -0,0 +1,125 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use crate::{
+    callable::Callable,
+    class::LoxInstance,
+    environment::Environment,
+    error::RuntimeError,
+    interpreter::{Interpreter, Unwind},
+    statement::Function,
+    value::Value,
+};
+
+/// A user-defined function together with the environment it was declared in.
+#[derive(Debug)]
+pub struct LoxFunction {
+    declaration: Rc<Function>,
+    closure: Rc<RefCell<Environment>>,
+    is_initializer: bool,
+}
+
+impl LoxFunction {
+    pub fn new(
+        declaration: Rc<Function>,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> Self {
+        LoxFunction {
+            declaration: declaration,
+            closure: closure,
+            is_initializer: is_initializer,
+        }
+    }
+
+    /// Returns a copy of this method whose closure has `this` bound to `instance`.
+    pub fn bind(&self, instance: Rc<RefCell<LoxInstance>>) -> LoxFunction {
+        let mut environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        environment.define("this".to_string(), Value::Instance(instance));
+        LoxFunction::new(
+            Rc::clone(&self.declaration),
+            Rc::new(RefCell::new(environment)),
+            self.is_initializer,
+        )
+    }
+
+    /// An initializer always evaluates to the instance it was called on.
+    fn this(&self) -> Value {
+        self.closure
+            .borrow()
+            .lookup_at(0, "this")
+            .unwrap_or(Value::Nil)
+    }
+}
+
+impl Callable for LoxFunction {
+    fn arity(&self) -> usize {
+        self.declaration.params().len()
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let mut environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        for (param, argument) in self.declaration.params().iter().zip(arguments) {
+            environment.define(param.lexeme(), argument);
+        }
+
+        match interpreter.execute_block(self.declaration.body(), environment) {
+            Ok(()) | Err(Unwind::Return(_)) if self.is_initializer => Ok(self.this()),
+            Ok(()) => Ok(Value::Nil),
+            Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Error(e)) => Err(e),
+        }
+    }
+}
+
+impl Display for LoxFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<fn {}>", self.declaration.name().lexeme())
+    }
+}
+
+/// A function implemented in Rust and exposed to Lox code as a global.
+pub struct NativeFunction {
+    name: String,
+    arity: usize,
+    function: fn(&[Value]) -> Value,
+}
+
+impl NativeFunction {
+    pub fn new(name: String, arity: usize, function: fn(&[Value]) -> Value) -> Self {
+        NativeFunction {
+            name: name,
+            arity: arity,
+            function: function,
+        }
+    }
+}
+
+impl Callable for NativeFunction {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        Ok((self.function)(&arguments))
+    }
+}
+
+impl std::fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NativeFunction({})", self.name)
+    }
+}
+
+impl Display for NativeFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn>")
+    }
+}
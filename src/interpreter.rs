@@ -0,0 +1,1164 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{self, Write},
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    bigint::{BigInt, MAX_BITS},
+    class::{LoxClass, LoxInstance},
+    environment::Environment,
+    error::RuntimeError,
+    expression::{Binary, Call, Expression, LiteralExp, Logical, Super, Unary, Update},
+    function::{LoxFunction, NativeFunction},
+    statement::{Class, Stmt},
+    token::{Literal, Token},
+    token_type::TokenType,
+    value::Value,
+};
+
+/// Ways in which executing a statement can stop early: a runtime error, or a
+/// `return` unwinding to the function call that is running it.
+pub enum Unwind {
+    Error(RuntimeError),
+    Return(Value),
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
+    environment: Rc<RefCell<Environment>>,
+    output: Box<dyn Write>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::with_output(Box::new(io::stdout()))
+    }
+
+    /// An interpreter whose `print` statements write to `output`.
+    pub fn with_output(output: Box<dyn Write>) -> Self {
+        let mut globals = Environment::new();
+        globals.define(
+            "clock".to_string(),
+            Value::NativeFunction(Rc::new(NativeFunction::new("clock".to_string(), 0, |_| {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+                Value::Float(now.as_secs_f64())
+            }))),
+        );
+
+        let globals = Rc::new(RefCell::new(globals));
+        Interpreter {
+            globals: Rc::clone(&globals),
+            environment: globals,
+            output: output,
+        }
+    }
+
+    pub fn interpret(&mut self, statements: &[Stmt]) -> Result<(), RuntimeError> {
+        for statement in statements {
+            match self.execute(statement) {
+                Ok(()) => (),
+                Err(Unwind::Error(e)) => return Err(e),
+                Err(Unwind::Return(_)) => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn execute(&mut self, statement: &Stmt) -> Result<(), Unwind> {
+        match statement {
+            Stmt::Expression(statement) => {
+                self.evaluate(statement.expression())?;
+            }
+            Stmt::Print(statement) => {
+                let value = self.evaluate(statement.expression())?;
+                writeln!(self.output, "{}", value).expect("failed printing to output");
+            }
+            Stmt::Var(statement) => {
+                let value = match statement.initializer() {
+                    Some(initializer) => self.evaluate(initializer)?,
+                    None => Value::Nil,
+                };
+                self.environment
+                    .borrow_mut()
+                    .define(statement.name().lexeme(), value);
+            }
+            Stmt::If(statement) => {
+                if self.evaluate(statement.condition())?.is_truthy() {
+                    self.execute(statement.then_branch())?;
+                } else if let Some(else_branch) = statement.else_branch() {
+                    self.execute(else_branch)?;
+                }
+            }
+            Stmt::While(statement) => {
+                while self.evaluate(statement.condition())?.is_truthy() {
+                    self.execute(statement.body())?;
+                }
+            }
+            Stmt::Block(statement) => {
+                let environment = Environment::with_enclosing(Rc::clone(&self.environment));
+                self.execute_block(statement.statements(), environment)?;
+            }
+            Stmt::Function(declaration) => {
+                let function =
+                    LoxFunction::new(Rc::clone(declaration), Rc::clone(&self.environment), false);
+                self.environment.borrow_mut().define(
+                    declaration.name().lexeme(),
+                    Value::Function(Rc::new(function)),
+                );
+            }
+            Stmt::Return(statement) => {
+                let value = match statement.value() {
+                    Some(value) => self.evaluate(value)?,
+                    None => Value::Nil,
+                };
+                return Err(Unwind::Return(value));
+            }
+            Stmt::Class(class) => self.class(class)?,
+        }
+
+        Ok(())
+    }
+
+    fn class(&mut self, class: &Class) -> Result<(), RuntimeError> {
+        let superclass = match class.superclass() {
+            Some(superclass) => {
+                match self.look_up_variable(superclass.name(), superclass.depth())? {
+                    Value::Class(superclass) => Some(superclass),
+                    _ => {
+                        return Err(Self::error(
+                            superclass.name(),
+                            "Superclass must be a class.",
+                        ))
+                    }
+                }
+            }
+            None => None,
+        };
+
+        self.environment
+            .borrow_mut()
+            .define(class.name().lexeme(), Value::Nil);
+
+        // Methods of a subclass close over an extra scope holding `super`.
+        let enclosing = Rc::clone(&self.environment);
+        if let Some(superclass) = &superclass {
+            let mut environment = Environment::with_enclosing(Rc::clone(&enclosing));
+            environment.define("super".to_string(), Value::Class(Rc::clone(superclass)));
+            self.environment = Rc::new(RefCell::new(environment));
+        }
+
+        let mut methods = HashMap::new();
+        for method in class.methods() {
+            let name = method.name().lexeme();
+            let is_initializer = name == "init";
+            let function = LoxFunction::new(
+                Rc::clone(method),
+                Rc::clone(&self.environment),
+                is_initializer,
+            );
+            methods.insert(name, Rc::new(function));
+        }
+
+        let lox_class = LoxClass::new(class.name().lexeme(), superclass, methods);
+        self.environment = enclosing;
+        self.environment
+            .borrow_mut()
+            .assign(class.name(), Value::Class(Rc::new(lox_class)))
+    }
+
+    /// Runs `statements` inside `environment`, restoring the current scope
+    /// afterwards even if execution unwinds early.
+    pub fn execute_block(
+        &mut self,
+        statements: &[Stmt],
+        environment: Environment,
+    ) -> Result<(), Unwind> {
+        let previous = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(environment)));
+
+        let mut result = Ok(());
+        for statement in statements {
+            result = self.execute(statement);
+            if result.is_err() {
+                break;
+            }
+        }
+
+        self.environment = previous;
+        result
+    }
+
+    fn evaluate(&mut self, expression: &Expression) -> Result<Value, RuntimeError> {
+        match expression {
+            Expression::LiteralExp(literal) => Ok(self.literal(literal)),
+            Expression::Grouping(grouping) => self.evaluate(grouping.expression()),
+            Expression::Unary(unary) => self.unary(unary),
+            Expression::Binary(binary) => self.binary(binary),
+            Expression::Logical(logical) => self.logical(logical),
+            Expression::Conditional(conditional) => {
+                if self.evaluate(conditional.condition())?.is_truthy() {
+                    self.evaluate(conditional.then_branch())
+                } else {
+                    self.evaluate(conditional.else_branch())
+                }
+            }
+            Expression::Call(call) => self.call(call),
+            Expression::Variable(variable) => {
+                self.look_up_variable(variable.name(), variable.depth())
+            }
+            Expression::Assign(assign) => {
+                let value = self.evaluate(assign.value())?;
+                self.assign_variable(assign.name(), assign.depth(), value.clone())?;
+                Ok(value)
+            }
+            Expression::This(this) => self.look_up_variable(this.keyword(), this.depth()),
+            Expression::Get(get) => match self.evaluate(get.object())? {
+                Value::Instance(instance) => LoxInstance::get(&instance, get.name()),
+                _ => Err(Self::error(get.name(), "Only instances have properties.")),
+            },
+            Expression::Set(set) => {
+                let instance = match self.evaluate(set.object())? {
+                    Value::Instance(instance) => instance,
+                    _ => return Err(Self::error(set.name(), "Only instances have fields.")),
+                };
+                let value = self.evaluate(set.value())?;
+                instance.borrow_mut().set(set.name(), value.clone());
+                Ok(value)
+            }
+            Expression::Super(expression) => self.super_method(expression),
+            Expression::Interpolation(interpolation) => {
+                let mut string = String::new();
+                for part in interpolation.parts() {
+                    string.push_str(&self.evaluate(part)?.to_string());
+                }
+                Ok(Value::String(string))
+            }
+            Expression::Update(update) => self.update(update),
+        }
+    }
+
+    fn update(&mut self, update: &Update) -> Result<Value, RuntimeError> {
+        let (old, new) = match update.target() {
+            Expression::Variable(variable) => {
+                let old = self.look_up_variable(variable.name(), variable.depth())?;
+                let new = self.updated_value(update, old.clone())?;
+                self.assign_variable(variable.name(), variable.depth(), new.clone())?;
+                (old, new)
+            }
+            Expression::Get(get) => {
+                let instance = match self.evaluate(get.object())? {
+                    Value::Instance(instance) => instance,
+                    _ => return Err(Self::error(get.name(), "Only instances have fields.")),
+                };
+                let old = LoxInstance::get(&instance, get.name())?;
+                let new = self.updated_value(update, old.clone())?;
+                instance.borrow_mut().set(get.name(), new.clone());
+                (old, new)
+            }
+            _ => return Err(Self::error(update.operator(), "Invalid assignment target.")),
+        };
+
+        if update.is_postfix() {
+            Ok(old)
+        } else {
+            Ok(new)
+        }
+    }
+
+    fn updated_value(&mut self, update: &Update, old: Value) -> Result<Value, RuntimeError> {
+        let value = match update.value() {
+            Some(value) => self.evaluate(value)?,
+            None => Value::Integer(1),
+        };
+
+        // Errors still point at the `+=` or `++` the user wrote.
+        let token = update.operator();
+        let operator = Token::new(
+            token.lexeme(),
+            update.binary_operator(),
+            token.line(),
+            token.column(),
+            token.offset(),
+            None,
+        );
+        Self::operate(&operator, old, value)
+    }
+
+    fn assign_variable(
+        &mut self,
+        name: &Token,
+        depth: Option<usize>,
+        value: Value,
+    ) -> Result<(), RuntimeError> {
+        match depth {
+            Some(depth) => self.environment.borrow_mut().assign_at(depth, name, value),
+            None => self.globals.borrow_mut().assign(name, value),
+        }
+    }
+
+    /// `super` lives one scope above the `this` bound for the current method.
+    fn super_method(&mut self, expression: &Super) -> Result<Value, RuntimeError> {
+        let depth = expression.depth().unwrap_or(0);
+        let superclass = match self.environment.borrow().lookup_at(depth, "super") {
+            Some(Value::Class(superclass)) => superclass,
+            _ => return Err(Self::error(expression.keyword(), "Superclass not found.")),
+        };
+        let instance = match self
+            .environment
+            .borrow()
+            .lookup_at(depth.saturating_sub(1), "this")
+        {
+            Some(Value::Instance(instance)) => instance,
+            _ => return Err(Self::error(expression.keyword(), "Instance not found.")),
+        };
+
+        match superclass.find_method(&expression.method().lexeme()) {
+            Some(method) => Ok(Value::Function(Rc::new(method.bind(instance)))),
+            None => Err(Self::error(
+                expression.method(),
+                &format!("Undefined property '{}'.", expression.method().lexeme()),
+            )),
+        }
+    }
+
+    fn look_up_variable(&self, name: &Token, depth: Option<usize>) -> Result<Value, RuntimeError> {
+        match depth {
+            Some(depth) => self.environment.borrow().get_at(depth, name),
+            None => self.globals.borrow().get(name),
+        }
+    }
+
+    fn literal(&self, literal: &LiteralExp) -> Value {
+        let token = literal.token();
+        match token.token_type() {
+            TokenType::TRUE => Value::Bool(true),
+            TokenType::FALSE => Value::Bool(false),
+            TokenType::NUMBER | TokenType::STRING | TokenType::INTERPOLATION => {
+                match token.literal() {
+                    Some(Literal::Integer(integer)) => Value::Integer(integer),
+                    Some(Literal::BigInt(integer)) => Value::from(integer),
+                    Some(Literal::Float(float)) => Value::Float(float),
+                    Some(Literal::String(string)) => Value::String(string),
+                    None => Value::Nil,
+                }
+            }
+            _ => Value::Nil,
+        }
+    }
+
+    fn call(&mut self, call: &Call) -> Result<Value, RuntimeError> {
+        let callee = self.evaluate(call.callee())?;
+
+        let mut arguments = Vec::new();
+        for argument in call.arguments() {
+            arguments.push(self.evaluate(argument)?);
+        }
+
+        let function = match callee.as_callable() {
+            Some(function) => function,
+            None => {
+                return Err(Self::error(
+                    call.paren(),
+                    "Can only call functions and classes.",
+                ))
+            }
+        };
+
+        if arguments.len() != function.arity() {
+            return Err(Self::error(
+                call.paren(),
+                &format!(
+                    "Expected {} arguments but got {}.",
+                    function.arity(),
+                    arguments.len()
+                ),
+            ));
+        }
+
+        function.call(self, arguments)
+    }
+
+    /// `and`/`or` short-circuit and yield the deciding operand itself, not a bool.
+    fn logical(&mut self, logical: &Logical) -> Result<Value, RuntimeError> {
+        let left = self.evaluate(logical.left())?;
+
+        if logical.operator().token_type() == TokenType::OR {
+            if left.is_truthy() {
+                return Ok(left);
+            }
+        } else if !left.is_truthy() {
+            return Ok(left);
+        }
+
+        self.evaluate(logical.right())
+    }
+
+    fn unary(&mut self, unary: &Unary) -> Result<Value, RuntimeError> {
+        let right = self.evaluate(unary.expression())?;
+        let operator = unary.operator();
+
+        match operator.token_type() {
+            TokenType::BANG => Ok(Value::Bool(!right.is_truthy())),
+            TokenType::MINUS => match right {
+                Value::Integer(integer) => match integer.checked_neg() {
+                    Some(negated) => Ok(Value::Integer(negated)),
+                    None => Ok(Value::from(-&BigInt::from(integer))),
+                },
+                Value::BigInt(integer) => Ok(Value::from(-&integer)),
+                Value::Float(float) => Ok(Value::Float(-float)),
+                _ => Err(Self::error(operator, "Operand must be a number.")),
+            },
+            TokenType::TILDE => match right {
+                Value::Integer(integer) => Ok(Value::Integer(!integer)),
+                Value::BigInt(integer) => Ok(Value::from(&-&integer - &BigInt::from(1))),
+                _ => Err(Self::error(operator, "Operand must be an integer.")),
+            },
+            _ => Err(Self::error(operator, "Invalid unary operator.")),
+        }
+    }
+
+    fn binary(&mut self, binary: &Binary) -> Result<Value, RuntimeError> {
+        let left = self.evaluate(binary.left())?;
+        let right = self.evaluate(binary.right())?;
+        Self::operate(binary.operator().token(), left, right)
+    }
+
+    fn operate(operator: &Token, left: Value, right: Value) -> Result<Value, RuntimeError> {
+        match operator.token_type() {
+            TokenType::COMMA => Ok(right),
+            TokenType::EQUAL_EQUAL => Ok(Value::Bool(left == right)),
+            TokenType::BANG_EQUAL => Ok(Value::Bool(left != right)),
+            TokenType::PLUS => match (left, right) {
+                (Value::String(a), Value::String(b)) => Ok(Value::String(a + &b)),
+                (left, right) if left.is_number() && right.is_number() => {
+                    Self::arithmetic(operator, left, right)
+                }
+                _ => Err(Self::error(
+                    operator,
+                    "Operands must be two numbers or two strings.",
+                )),
+            },
+            TokenType::AMPERSAND
+            | TokenType::PIPE
+            | TokenType::CARET
+            | TokenType::LESS_LESS
+            | TokenType::GREATER_GREATER => Self::bitwise(operator, left, right),
+            _ => Self::arithmetic(operator, left, right),
+        }
+    }
+
+    /// Bitwise operators treat integers as infinite two's complement, so
+    /// they agree between `Integer` and `BigInt`. `<<` grows into a `BigInt`
+    /// rather than overflow, up to `MAX_BITS`.
+    fn bitwise(operator: &Token, left: Value, right: Value) -> Result<Value, RuntimeError> {
+        if let (Value::Integer(a), Value::Integer(b)) = (&left, &right) {
+            let (a, b) = (*a, *b);
+            let result = match operator.token_type() {
+                TokenType::AMPERSAND => Some(a & b),
+                TokenType::PIPE => Some(a | b),
+                TokenType::CARET => Some(a ^ b),
+                TokenType::LESS_LESS if (0..64).contains(&b) && (a << b) >> b == a => Some(a << b),
+                TokenType::GREATER_GREATER if (0..64).contains(&b) => Some(a >> b),
+                _ => None,
+            };
+            if let Some(result) = result {
+                return Ok(Value::Integer(result));
+            }
+        }
+
+        let (a, b) = match left.as_big_int().zip(right.as_big_int()) {
+            Some(operands) => operands,
+            None => return Err(Self::error(operator, "Operands must be integers.")),
+        };
+
+        let result = match operator.token_type() {
+            TokenType::AMPERSAND => &a & &b,
+            TokenType::PIPE => &a | &b,
+            TokenType::CARET => &a ^ &b,
+            TokenType::LESS_LESS => {
+                let amount = Self::shift_amount(operator, &b)?;
+                if !a.is_zero() && a.bits().saturating_add(amount) > MAX_BITS {
+                    return Err(Self::error(operator, "Shift amount is too large."));
+                }
+                a.shift_left(amount)
+            }
+            TokenType::GREATER_GREATER => a.shift_right(Self::shift_amount(operator, &b)?),
+            _ => return Err(Self::error(operator, "Invalid binary operator.")),
+        };
+
+        Ok(Value::from(result))
+    }
+
+    fn shift_amount(operator: &Token, amount: &BigInt) -> Result<u64, RuntimeError> {
+        if amount < &BigInt::from(0) {
+            return Err(Self::error(operator, "Shift amount can't be negative."));
+        }
+        match amount.to_i64() {
+            Some(amount) => Ok(amount as u64),
+            None => Err(Self::error(operator, "Shift amount is too large.")),
+        }
+    }
+
+    /// Two integers stay integral, except under `/` and negative `**` which
+    /// yield floats. As soon as a float is involved both operands become floats.
+    fn arithmetic(operator: &Token, left: Value, right: Value) -> Result<Value, RuntimeError> {
+        let operands = match (&left, &right) {
+            (Value::Integer(a), Value::Integer(b)) => {
+                return Self::integer_arithmetic(operator, *a, *b)
+            }
+            (Value::Float(_), _) | (_, Value::Float(_)) => {
+                if let (Some(a), Some(b)) = (left.as_float(), right.as_float()) {
+                    return Self::float_arithmetic(operator, a, b);
+                }
+                None
+            }
+            _ => left.as_big_int().zip(right.as_big_int()),
+        };
+
+        match operands {
+            Some((a, b)) => Self::big_arithmetic(operator, &a, &b),
+            None => Err(Self::error(operator, "Operands must be numbers.")),
+        }
+    }
+
+    /// Falls back to `BigInt` when the result doesn't fit in an `i64`.
+    fn integer_arithmetic(operator: &Token, a: i64, b: i64) -> Result<Value, RuntimeError> {
+        let result = match operator.token_type() {
+            TokenType::PLUS => a.checked_add(b),
+            TokenType::MINUS => a.checked_sub(b),
+            TokenType::STAR => a.checked_mul(b),
+            TokenType::SLASH => return Ok(Value::Float(a as f64 / b as f64)),
+            TokenType::TILDE_SLASH => {
+                if b == 0 {
+                    return Err(Self::error(operator, "Division by zero."));
+                }
+                // Rust's `/` truncates toward zero; step down when the exact
+                // quotient is negative and not whole.
+                a.checked_div(b).map(|quotient| {
+                    if a % b != 0 && (a < 0) != (b < 0) {
+                        quotient - 1
+                    } else {
+                        quotient
+                    }
+                })
+            }
+            // The remainder of `~/`, so it takes the sign of the divisor.
+            TokenType::PERCENT => {
+                if b == 0 {
+                    return Err(Self::error(operator, "Division by zero."));
+                }
+                a.checked_rem(b).map(|remainder| {
+                    if remainder != 0 && (remainder < 0) != (b < 0) {
+                        remainder + b
+                    } else {
+                        remainder
+                    }
+                })
+            }
+            TokenType::STAR_STAR => {
+                if b < 0 {
+                    return Ok(Value::Float((a as f64).powf(b as f64)));
+                }
+                u32::try_from(b).ok().and_then(|b| a.checked_pow(b))
+            }
+            TokenType::GREATER => return Ok(Value::Bool(a > b)),
+            TokenType::GREATER_EQUAL => return Ok(Value::Bool(a >= b)),
+            TokenType::LESS => return Ok(Value::Bool(a < b)),
+            TokenType::LESS_EQUAL => return Ok(Value::Bool(a <= b)),
+            _ => return Err(Self::error(operator, "Invalid binary operator.")),
+        };
+
+        match result {
+            Some(result) => Ok(Value::Integer(result)),
+            None => Self::big_arithmetic(operator, &BigInt::from(a), &BigInt::from(b)),
+        }
+    }
+
+    fn big_arithmetic(operator: &Token, a: &BigInt, b: &BigInt) -> Result<Value, RuntimeError> {
+        let result = match operator.token_type() {
+            TokenType::PLUS => a + b,
+            TokenType::MINUS => a - b,
+            TokenType::STAR => {
+                // The product has at least `bits(a) + bits(b) - 1` bits.
+                if !a.is_zero() && !b.is_zero() && a.bits() + b.bits() - 1 > MAX_BITS {
+                    return Err(Self::error(operator, "Product is too large."));
+                }
+                a * b
+            }
+            TokenType::SLASH => return Ok(Value::Float(a.to_f64() / b.to_f64())),
+            TokenType::TILDE_SLASH => match a.div_mod_floor(b) {
+                Some((quotient, _)) => quotient,
+                None => return Err(Self::error(operator, "Division by zero.")),
+            },
+            TokenType::PERCENT => match a.div_mod_floor(b) {
+                Some((_, remainder)) => remainder,
+                None => return Err(Self::error(operator, "Division by zero.")),
+            },
+            TokenType::STAR_STAR => {
+                if b < &BigInt::from(0) {
+                    return Ok(Value::Float(a.to_f64().powf(b.to_f64())));
+                }
+                // The result has at least `(bits - 1) * b` bits, so refuse
+                // early rather than grind through a result nobody can use.
+                match b.to_i64().and_then(|b| u32::try_from(b).ok()) {
+                    Some(b) if (a.bits().max(1) - 1) * b as u64 <= MAX_BITS => a.pow(b),
+                    _ => return Err(Self::error(operator, "Exponent is too large.")),
+                }
+            }
+            TokenType::GREATER => return Ok(Value::Bool(a > b)),
+            TokenType::GREATER_EQUAL => return Ok(Value::Bool(a >= b)),
+            TokenType::LESS => return Ok(Value::Bool(a < b)),
+            TokenType::LESS_EQUAL => return Ok(Value::Bool(a <= b)),
+            _ => return Err(Self::error(operator, "Invalid binary operator.")),
+        };
+
+        Ok(Value::from(result))
+    }
+
+    fn float_arithmetic(operator: &Token, a: f64, b: f64) -> Result<Value, RuntimeError> {
+        match operator.token_type() {
+            TokenType::PLUS => Ok(Value::Float(a + b)),
+            TokenType::MINUS => Ok(Value::Float(a - b)),
+            TokenType::STAR => Ok(Value::Float(a * b)),
+            TokenType::SLASH => Ok(Value::Float(a / b)),
+            TokenType::TILDE_SLASH => Ok(Value::Float((a / b).floor())),
+            TokenType::PERCENT => {
+                let remainder = a % b;
+                if remainder != 0.0 && (remainder < 0.0) != (b < 0.0) {
+                    Ok(Value::Float(remainder + b))
+                } else {
+                    Ok(Value::Float(remainder))
+                }
+            }
+            TokenType::STAR_STAR => Ok(Value::Float(a.powf(b))),
+            TokenType::GREATER => Ok(Value::Bool(a > b)),
+            TokenType::GREATER_EQUAL => Ok(Value::Bool(a >= b)),
+            TokenType::LESS => Ok(Value::Bool(a < b)),
+            TokenType::LESS_EQUAL => Ok(Value::Bool(a <= b)),
+            _ => Err(Self::error(operator, "Invalid binary operator.")),
+        }
+    }
+
+    fn error(token: &Token, message: &str) -> RuntimeError {
+        RuntimeError::new(token.clone(), message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::Parser, resolver::Resolver, scanner::Scanner};
+
+    /// Collects what `print` writes.
+    #[derive(Clone, Default)]
+    struct Output(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn interpret_to(
+        source: &str,
+        output: Box<dyn Write>,
+    ) -> (Interpreter, Result<(), RuntimeError>) {
+        let tokens = Scanner::new(source.to_string()).scan_tokens();
+        let statements = Parser::new(tokens).parse().expect("source should parse");
+        Resolver::new()
+            .resolve(&statements)
+            .expect("source should resolve");
+        let mut interpreter = Interpreter::with_output(output);
+        let result = interpreter.interpret(&statements);
+        (interpreter, result)
+    }
+
+    fn interpret(source: &str) -> (Interpreter, Result<(), RuntimeError>) {
+        interpret_to(source, Box::new(io::sink()))
+    }
+
+    /// Runs `source` and returns everything it printed.
+    fn printed(source: &str) -> String {
+        let output = Output::default();
+        if let Err(error) = interpret_to(source, Box::new(output.clone())).1 {
+            panic!("unexpected runtime error: {}", error);
+        }
+        let bytes = output.0.borrow().clone();
+        String::from_utf8(bytes).expect("output should be UTF-8")
+    }
+
+    /// Runs `source` and returns the value of the global `name`.
+    fn global(source: &str, name: &str) -> Value {
+        let (interpreter, result) = interpret(source);
+        if let Err(error) = result {
+            panic!("unexpected runtime error: {}", error);
+        }
+        let value = interpreter.globals.borrow().lookup_at(0, name);
+        value.expect("global should be defined")
+    }
+
+    /// Runs `source` and returns the global `name` as it would be printed.
+    fn run(source: &str, name: &str) -> String {
+        global(source, name).to_string()
+    }
+
+    fn evaluate(expression: &str) -> Value {
+        global(&format!("var result = {};", expression), "result")
+    }
+
+    fn value_of(expression: &str) -> String {
+        evaluate(expression).to_string()
+    }
+
+    /// Runs `source` and returns the message of the runtime error it raises.
+    fn run_error(source: &str) -> String {
+        run_error_at(source).2
+    }
+
+    /// Like `run_error`, with the line and column of the token it points at.
+    fn run_error_at(source: &str) -> (usize, usize, String) {
+        match interpret(source).1 {
+            Ok(()) => panic!("expected a runtime error"),
+            Err(error) => (
+                error.token().line(),
+                error.token().column(),
+                error.message(),
+            ),
+        }
+    }
+
+    #[test]
+    fn refuses_powers_that_would_be_too_large() {
+        assert_eq!(run("var x = 1 ** 3000000000;", "x"), "1");
+        assert_eq!(run("var x = (-1) ** 3000000001;", "x"), "-1");
+        assert_eq!(run("var x = 0 ** 3000000000;", "x"), "0");
+        assert_eq!(run("var x = (2 ** 100000) % 1000;", "x"), "376");
+        assert_eq!(run_error("3 ** 3000000000;"), "Exponent is too large.");
+        assert_eq!(run_error("2 ** 300000;"), "Exponent is too large.");
+        assert_eq!(run_error("(2 ** 100) ** 10000;"), "Exponent is too large.");
+    }
+
+    #[test]
+    fn refuses_products_that_would_be_too_large() {
+        assert_eq!(
+            run("var x = (1 << 100000) * (1 << 100000) == 1 << 200000;", "x"),
+            "true"
+        );
+        assert_eq!(run("var x = (1 << 200000) * 0;", "x"), "0");
+        assert_eq!(
+            run_error("var x = 1 << 100000; x = x * x * x;"),
+            "Product is too large."
+        );
+        assert_eq!(
+            run_error("(1 << 131072) * (1 << 131072);"),
+            "Product is too large."
+        );
+    }
+
+    #[test]
+    fn bitwise_operators_work_on_negative_integers() {
+        let cases = [
+            ("-6 & 3", "2"),
+            ("-6 | 3", "-5"),
+            ("-6 ^ 3", "-7"),
+            ("~-6", "5"),
+            ("-5 << 3", "-40"),
+            ("-5 >> 1", "-3"),
+            ("-1 >> 100", "-1"),
+            ("5 >> 100", "0"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(value_of(expression), expected, "{}", expression);
+        }
+    }
+
+    #[test]
+    fn bitwise_operators_work_on_big_integers() {
+        let b = "(2 ** 70 + 5)";
+        let cases = [
+            (format!("{} & -{}", b, b), "1"),
+            (format!("-{} | 12345", b), "-1180591620717411303429"),
+            (format!("{} ^ -1", b), "-1180591620717411303430"),
+            (format!("~{}", b), "-1180591620717411303430"),
+            (
+                format!("-{} << 40", b),
+                "-1298074214633706907138121640443904",
+            ),
+            (format!("-{} >> 3", b), "-147573952589676412929"),
+            (format!("{} >> 3", b), "147573952589676412928"),
+            (format!("-{} >> 64", b), "-65"),
+            ("1 << 64".to_string(), "18446744073709551616"),
+            ("-(2 ** 64) >> 64".to_string(), "-1"),
+            ("-(2 ** 64 + 1) >> 64".to_string(), "-2"),
+            ("(3 << 100) >> 99".to_string(), "6"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(value_of(&expression), expected, "{}", expression);
+        }
+    }
+
+    #[test]
+    fn refuses_shifts_past_the_size_limit() {
+        assert_eq!(value_of("0 << 3000000000"), "0");
+        assert_eq!(value_of("(1 << 70) >> 3000000000"), "0");
+        assert_eq!(run_error("1 << 3000000000;"), "Shift amount is too large.");
+        assert_eq!(run_error("1 << 262144;"), "Shift amount is too large.");
+        assert_eq!(run_error("1 << -1;"), "Shift amount can't be negative.");
+        assert_eq!(run_error("1.5 << 1;"), "Operands must be integers.");
+    }
+
+    #[test]
+    fn update_evaluates_the_target_object_once() {
+        let source = r#"
+            class Box {}
+            var box = Box();
+            box.x = 1;
+            var calls = 0;
+            fun obj() {
+              calls = calls + 1;
+              return box;
+            }
+            obj().x += 1;
+            obj().x++;
+            --obj().x;
+            var x = box.x;
+        "#;
+        assert_eq!(run(source, "calls"), "3");
+        assert_eq!(run(source, "x"), "2");
+    }
+
+    #[test]
+    fn postfix_returns_the_old_value_and_prefix_the_new_one() {
+        let source = r#"
+            var i = 5;
+            var postfix = i++;
+            var prefix = ++i;
+            var decrement = i--;
+            var compound = (i += 10);
+        "#;
+        assert_eq!(run(source, "postfix"), "5");
+        assert_eq!(run(source, "prefix"), "7");
+        assert_eq!(run(source, "decrement"), "7");
+        assert_eq!(run(source, "compound"), "16");
+        assert_eq!(run(source, "i"), "16");
+    }
+
+    #[test]
+    fn increment_grows_past_i64_max() {
+        let source =
+            "var i = 9223372036854775807; var old = i++; var j = -9223372036854775808; j--;";
+        assert_eq!(run(source, "old"), "9223372036854775807");
+        assert_eq!(run(source, "i"), "9223372036854775808");
+        assert_eq!(run(source, "j"), "-9223372036854775809");
+    }
+
+    #[test]
+    fn conditional_evaluates_only_the_chosen_branch() {
+        let source = r#"
+            var log = "";
+            fun note(name) {
+              log = log + name;
+              return name;
+            }
+            var first = true ? note("then") : note("else");
+            var second = nil ? note("then") : note("else");
+            var nested = false ? note("a") : false ? note("b") : note("c");
+        "#;
+        assert_eq!(run(source, "first"), "then");
+        assert_eq!(run(source, "second"), "else");
+        assert_eq!(run(source, "nested"), "c");
+        assert_eq!(run(source, "log"), "thenelsec");
+    }
+
+    #[test]
+    fn slash_always_divides_to_a_float() {
+        assert!(matches!(evaluate("10 / 2"), Value::Float(quotient) if quotient == 5.0));
+        assert!(matches!(evaluate("7 / 2"), Value::Float(quotient) if quotient == 3.5));
+        assert!(matches!(evaluate("1 / 0"), Value::Float(quotient) if quotient.is_infinite()));
+    }
+
+    #[test]
+    fn floor_division_rounds_toward_negative_infinity() {
+        let cases = [
+            ("7 ~/ 2", "3"),
+            ("-7 ~/ 2", "-4"),
+            ("7 ~/ -2", "-4"),
+            ("-7 ~/ -2", "3"),
+            ("7.5 ~/ 2", "3"),
+            ("-7.5 ~/ 2", "-4"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(value_of(expression), expected, "{}", expression);
+        }
+        assert!(matches!(evaluate("7 ~/ 2"), Value::Integer(3)));
+        assert!(matches!(evaluate("7.5 ~/ 2"), Value::Float(_)));
+        assert_eq!(run_error("1 ~/ 0;"), "Division by zero.");
+    }
+
+    #[test]
+    fn modulo_takes_the_sign_of_the_divisor() {
+        let cases = [
+            ("7 % 3", "1"),
+            ("-7 % 3", "2"),
+            ("7 % -3", "-2"),
+            ("-7 % -3", "-1"),
+            ("-7.5 % 2", "0.5"),
+            ("7.5 % -2", "-0.5"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(value_of(expression), expected, "{}", expression);
+        }
+        assert_eq!(run_error("1 % 0;"), "Division by zero.");
+    }
+
+    #[test]
+    fn mixing_integers_and_floats_gives_a_float() {
+        assert!(matches!(evaluate("1 + 2"), Value::Integer(3)));
+        assert!(matches!(evaluate("1 + 2.0"), Value::Float(sum) if sum == 3.0));
+        assert!(matches!(evaluate("2.5 * 2"), Value::Float(product) if product == 5.0));
+        assert!(matches!(evaluate("2 ** -1"), Value::Float(power) if power == 0.5));
+        assert_eq!(value_of("1 == 1.0"), "true");
+    }
+
+    #[test]
+    fn floats_print_without_a_trailing_zero_and_without_exponents() {
+        let cases = [
+            ("10 / 2", "5"),
+            ("2.50", "2.5"),
+            ("-0.0", "-0"),
+            ("1e16", "10000000000000000"),
+            ("1e-7", "0.0000001"),
+            ("0.1 + 0.2", "0.30000000000000004"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(value_of(expression), expected, "{}", expression);
+        }
+    }
+
+    #[test]
+    fn evaluates_arithmetic_comparison_and_equality() {
+        let cases = [
+            ("1 + 2 * 3", "7"),
+            ("(1 + 2) * 3", "9"),
+            ("-(4 - 6)", "2"),
+            ("2 ** 3 ** 2", "512"),
+            ("1 < 2", "true"),
+            ("2 <= 1", "false"),
+            ("1 == 1.0", "true"),
+            ("\"a\" == \"a\"", "true"),
+            ("nil == nil", "true"),
+            ("nil == false", "false"),
+            ("1 != \"1\"", "true"),
+            ("\"ab\" + \"cd\"", "abcd"),
+            ("!nil", "true"),
+            ("!0", "false"),
+            ("!\"\"", "false"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(value_of(expression), expected, "{}", expression);
+        }
+    }
+
+    #[test]
+    fn runtime_errors_point_at_the_operator() {
+        assert_eq!(
+            run_error_at("var a = 1;\nprint -\"abc\";"),
+            (2, 7, "Operand must be a number.".to_string())
+        );
+        assert_eq!(
+            run_error_at("print 1 <\n  \"x\";"),
+            (1, 9, "Operands must be numbers.".to_string())
+        );
+        assert_eq!(
+            run_error_at("print 1 + nil;"),
+            (
+                1,
+                9,
+                "Operands must be two numbers or two strings.".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn prints_values_and_discards_expression_statements() {
+        assert_eq!(
+            printed("print 1 + 2;\n\"ignored\";\nprint \"a\";\nprint nil;\nprint true;"),
+            "3\na\nnil\ntrue\n"
+        );
+    }
+
+    #[test]
+    fn blocks_shadow_outer_variables_and_restore_them_on_exit() {
+        let source = "
+            var a = \"global\";
+            { var a = \"outer\"; { var a = \"inner\"; print a; } print a; }
+            print a;
+            var b = 1;
+            { b = 2; }
+            print b;
+            var c; var d; c = d = 3;
+            print c + d;
+        ";
+        assert_eq!(printed(source), "inner\nouter\nglobal\n2\n6\n");
+    }
+
+    #[test]
+    fn undefined_variables_are_runtime_errors() {
+        assert_eq!(
+            run_error_at("var a = 1;\nb = 2;"),
+            (2, 1, "Undefined variable 'b'.".to_string())
+        );
+        assert_eq!(
+            run_error_at("print a;"),
+            (1, 7, "Undefined variable 'a'.".to_string())
+        );
+    }
+
+    #[test]
+    fn if_while_and_for_control_flow() {
+        let source = "
+            if (false) print 1; else print 2;
+            if (nil) if (true) print 3; else print 4;
+            var i = 0;
+            while (i < 3) { print i; i = i + 1; }
+            for (var j = 10; j < 13; j = j + 1) print j;
+            var k = 0;
+            for (; k < 2;) k = k + 1;
+            print k;
+        ";
+        assert_eq!(printed(source), "2\n0\n1\n2\n10\n11\n12\n2\n");
+    }
+
+    #[test]
+    fn for_loop_variables_are_scoped_to_the_loop() {
+        assert_eq!(
+            run_error("for (var i = 0; i < 1; i = i + 1) {} print i;"),
+            "Undefined variable 'i'."
+        );
+    }
+
+    #[test]
+    fn logical_operators_short_circuit_and_return_an_operand() {
+        assert_eq!(value_of("nil or \"yes\""), "yes");
+        assert_eq!(value_of("1 or 2"), "1");
+        assert_eq!(value_of("nil and 2"), "nil");
+        assert_eq!(value_of("1 and 2"), "2");
+        assert_eq!(
+            printed("var a = 0; false and (a = 1); true or (a = 2); print a;"),
+            "0\n"
+        );
+    }
+
+    #[test]
+    fn functions_return_values_and_check_their_arity() {
+        assert_eq!(
+            printed("fun add(a, b) { return a + b; } print add(1, 2);"),
+            "3\n"
+        );
+        assert_eq!(
+            printed("fun f() { return; } print f(); fun g() {} print g();"),
+            "nil\nnil\n"
+        );
+        assert_eq!(
+            printed("fun f(n) { while (true) if (n > 2) return n; else n = n + 1; } print f(0);"),
+            "3\n"
+        );
+        assert_eq!(
+            run_error_at("fun f(a, b) {}\nf(1);"),
+            (2, 4, "Expected 2 arguments but got 1.".to_string())
+        );
+        assert_eq!(
+            run_error_at("\"not a function\"();"),
+            (1, 18, "Can only call functions and classes.".to_string())
+        );
+    }
+
+    #[test]
+    fn closures_capture_their_defining_environment() {
+        let source = "
+            fun counter() {
+                var count = 0;
+                fun increment() { count = count + 1; return count; }
+                return increment;
+            }
+            var a = counter();
+            var b = counter();
+            print a(); print a(); print b();
+        ";
+        assert_eq!(printed(source), "1\n2\n1\n");
+    }
+
+    #[test]
+    fn init_returns_the_instance() {
+        let source = "
+            class Point {
+                init(x) { this.x = x; return; }
+            }
+            var p = Point(1);
+            print p.init(2) == p;
+            print p.x;
+        ";
+        assert_eq!(printed(source), "true\n2\n");
+    }
+
+    #[test]
+    fn methods_stay_bound_to_their_instance() {
+        let source = "
+            class Greeter {
+                init(name) { this.name = name; }
+                greet() { return \"hi \" + this.name; }
+            }
+            var greet = Greeter(\"bob\").greet;
+            print greet();
+        ";
+        assert_eq!(printed(source), "hi bob\n");
+    }
+
+    #[test]
+    fn super_calls_the_superclass_method() {
+        let source = "
+            class A { name() { return \"A\"; } describe() { return this.name(); } }
+            class B < A { name() { return \"B and \" + super.name(); } }
+            class C < B {}
+            print C().describe();
+        ";
+        assert_eq!(printed(source), "B and A\n");
+    }
+
+    #[test]
+    fn inheriting_from_a_non_class_is_a_runtime_error() {
+        assert_eq!(
+            run_error_at("var NotAClass = 1;\nclass A < NotAClass {}"),
+            (2, 11, "Superclass must be a class.".to_string())
+        );
+    }
+
+    #[test]
+    fn floats_keep_f64_precision() {
+        assert_eq!(value_of("16777217.0"), "16777217");
+        assert_eq!(value_of("16777217.0 + 1"), "16777218");
+        assert_eq!(value_of("0.1 + 0.2 == 0.3"), "false");
+    }
+}
@@ -0,0 +1,24 @@
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+
+pub mod ast_printer;
+pub mod bigint;
+pub mod callable;
+pub mod class;
+pub mod diagnostic;
+pub mod dump;
+pub mod environment;
+pub mod error;
+pub mod expression;
+pub mod function;
+pub mod interpreter;
+pub mod json;
+pub mod lox;
+pub mod parser;
+pub mod resolver;
+pub mod scanner;
+pub mod span;
+pub mod statement;
+pub mod token;
+pub mod token_type;
+pub mod value;
+pub mod visitor;
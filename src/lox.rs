@@ -1,32 +1,86 @@
-use crate::{expression::Expression, parser::Parser, scanner::Scanner, token::Token};
+use crate::{
+    diagnostic::Reporter, dump::dump, interpreter::Interpreter, json::Json, parser::Parser,
+    resolver::Resolver, scanner::Scanner,
+};
 use std::{fs::read, io, path::Path, process::exit};
 
 pub struct Lox {
     has_error: bool,
+    has_runtime_error: bool,
+    interpreter: Interpreter,
+}
+
+impl Default for Lox {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Lox {
     pub fn new() -> Self {
-        Lox { has_error: false }
+        Lox {
+            has_error: false,
+            has_runtime_error: false,
+            interpreter: Interpreter::new(),
+        }
     }
 
     pub fn start(&mut self, args: Vec<String>) {
-        if args.len() > 2 {
-            eprintln!("Usage: rustox [script]");
+        if args.len() == 3 && args[1] == "--dump-json" {
+            self.dump_file(args[2].clone());
+        } else if args.len() > 2 {
+            eprintln!("Usage: rustox [--dump-json] [script]");
             exit(1);
         } else if args.len() == 2 {
             self.run_file(args[1].clone());
         } else {
             println!("To terminate REPL press enter.");
-            self.run_prompt();
+            if let Err(e) = self.run_prompt() {
+                eprintln!("ERROR: {}", e);
+                exit(74);
+            }
         }
     }
 
     pub fn run_file(&mut self, path: String) {
-        let file_path = Path::new(&path[..]);
-        let bytes: Vec<u8> = read(file_path).unwrap();
-        let source = String::from_utf8(bytes).unwrap();
-        self.run(source);
+        let source = Self::read_source(&path);
+        self.run(source, &path);
+
+        if let Some(code) = self.exit_code() {
+            exit(code);
+        }
+    }
+
+    /// 65 after a static error and 70 after a runtime error, like the
+    /// reference implementation.
+    fn exit_code(&self) -> Option<i32> {
+        if self.has_error {
+            return Some(65);
+        }
+        if self.has_runtime_error {
+            return Some(70);
+        }
+        None
+    }
+
+    /// Prints the tokens and syntax tree of a script as JSON instead of
+    /// running it. See `dump::dump` for the format. Exits with 65, like
+    /// `run_file`, if the script has errors.
+    pub fn dump_file(&mut self, path: String) {
+        let source = Self::read_source(&path);
+        let json = dump(&source);
+        println!("{}", json);
+
+        if let Some(Json::Array(errors)) = json.get("errors") {
+            if !errors.is_empty() {
+                exit(65);
+            }
+        }
+    }
+
+    fn read_source(path: &str) -> String {
+        let bytes: Vec<u8> = read(Path::new(path)).unwrap();
+        String::from_utf8(bytes).unwrap()
     }
 
     pub fn run_prompt(&mut self) -> io::Result<()> {
@@ -36,8 +90,9 @@ impl Lox {
             match stdin.read_line(&mut buffer) {
                 Ok(2) => break,
                 Ok(_n) => {
-                    self.run(buffer);
+                    self.run(buffer, "<repl>");
                     self.has_error = false;
+                    self.has_runtime_error = false;
                 }
                 Err(e) => eprintln!("ERROR: {}", e),
             }
@@ -46,25 +101,69 @@ impl Lox {
         Ok(())
     }
 
-    fn run(&mut self, source: String) {
+    fn run(&mut self, source: String, file_name: &str) {
+        let reporter = Reporter::new(file_name.to_string(), source.clone());
+
         let mut scanner = Scanner::new(source);
         let tokens = scanner.scan_tokens();
+        for e in scanner.errors() {
+            eprintln!("{}\n", reporter.error(e));
+            self.has_error = true;
+        }
+
+        // Parse even after lexical errors so syntax errors get reported too.
         let mut parser = Parser::new(tokens);
+        let statements = match parser.parse() {
+            Ok(statements) => statements,
+            Err(errors) => {
+                for e in errors {
+                    eprintln!("{}\n", reporter.error(&e));
+                }
+                self.has_error = true;
+                return;
+            }
+        };
+
+        if self.has_error {
+            return;
+        }
+
+        if let Err(errors) = Resolver::new().resolve(&statements) {
+            for e in errors {
+                eprintln!("{}\n", reporter.error(&e));
+            }
+            self.has_error = true;
+            return;
+        }
 
-        match parser.parse() {
-            Ok(expr) => println!("{:?}", expr),
-            Err(e) => eprintln!("{:?}", e),
+        if let Err(e) = self.interpreter.interpret(&statements) {
+            eprintln!("{}\n", reporter.runtime_error(&e));
+            self.has_runtime_error = true;
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The exit code `run_file` would use for `source`.
+    fn exit_code(source: &str) -> Option<i32> {
+        let mut lox = Lox::new();
+        lox.run(source.to_string(), "test.lox");
+        lox.exit_code()
+    }
 
-    /*
-    fn error(&mut self, line: usize, message: String) {
-        self.report(line, "".to_string(), message);
+    #[test]
+    fn exits_with_70_after_a_runtime_error() {
+        assert_eq!(exit_code("1 + 2;"), None);
+        assert_eq!(exit_code("print -\"abc\";"), Some(70));
+        assert_eq!(exit_code("print 1 +;"), Some(65));
     }
 
-    fn report(&mut self, line: usize, where_happened: String, message: String) {
-        eprintln!("[{line}] Error {where_happened}: {message}");
-        self.has_error = Some(Error::new(line, message));
+    #[test]
+    fn number_literals_too_large_for_f64_are_static_errors() {
+        assert_eq!(exit_code("1.5e308;"), None);
+        assert_eq!(exit_code("1e400;"), Some(65));
     }
-    */
 }
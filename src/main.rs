@@ -1,13 +1,7 @@
-mod error;
-mod expression;
-mod lox;
-mod scanner;
-mod token;
-mod token_type;
-mod parser;
 use std::env;
 
-use lox::Lox;
+use rustox::lox::Lox;
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     let mut lox = Lox::new();
@@ -1,13 +1,99 @@
+use std::rc::Rc;
+
 use crate::{
     error::Error,
-    expression::{Binary, Expression, Grouping, LiteralExp, Operator, Unary},
+    expression::{
+        Assign, Binary, Call, Conditional, Expression, Get, Grouping, Interpolation, LiteralExp,
+        Logical, Operator, Set, Super, This, Unary, Update, Variable,
+    },
+    span::Span,
+    statement::{Block, Class, ExpressionStmt, Function, If, Print, Return, Stmt, Var, While},
     token::Token,
     token_type::TokenType,
 };
 
+const MAX_ARGUMENTS: usize = 255;
+
+type PrefixRule = fn(&mut Parser) -> Result<Expression, Error>;
+type InfixRule = fn(&mut Parser, Expression) -> Result<Expression, Error>;
+
+/// How tightly an operator binds, from loosest to tightest.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Precedence {
+    None,
+    Comma,       // ,
+    Assignment,  // = += -= *= /= %=
+    Conditional, // ?:
+    Or,          // or
+    And,         // and
+    Equality,    // == !=
+    Comparison,  // < > <= >=
+    BitOr,       // |
+    BitXor,      // ^
+    BitAnd,      // &
+    Shift,       // << >>
+    Term,        // + -
+    Factor,      // * / ~/ %
+    Unary,       // ! - ~
+    Exponent,    // **
+    Postfix,     // ++ --
+    Call,        // () .
+}
+
+impl Precedence {
+    fn next(self) -> Precedence {
+        match self {
+            Precedence::None => Precedence::Comma,
+            Precedence::Comma => Precedence::Assignment,
+            Precedence::Assignment => Precedence::Conditional,
+            Precedence::Conditional => Precedence::Or,
+            Precedence::Or => Precedence::And,
+            Precedence::And => Precedence::Equality,
+            Precedence::Equality => Precedence::Comparison,
+            Precedence::Comparison => Precedence::BitOr,
+            Precedence::BitOr => Precedence::BitXor,
+            Precedence::BitXor => Precedence::BitAnd,
+            Precedence::BitAnd => Precedence::Shift,
+            Precedence::Shift => Precedence::Term,
+            Precedence::Term => Precedence::Factor,
+            Precedence::Factor => Precedence::Unary,
+            Precedence::Unary => Precedence::Exponent,
+            Precedence::Exponent => Precedence::Postfix,
+            Precedence::Postfix => Precedence::Call,
+            Precedence::Call => Precedence::Call,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Associativity {
+    Left,
+    Right,
+}
+
+/// One row of the parse table, see `Parser::rule`.
+struct ParseRule {
+    prefix: Option<PrefixRule>,
+    infix: Option<InfixRule>,
+    precedence: Precedence,
+    associativity: Associativity,
+}
+
+impl ParseRule {
+    /// The precedence to parse an infix operator's right operand at. A
+    /// left-associative operator stops at another operator of its own level.
+    fn operand_precedence(&self) -> Precedence {
+        match self.associativity {
+            Associativity::Left => self.precedence.next(),
+            Associativity::Right => self.precedence,
+        }
+    }
+}
+
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    errors: Vec<Error>,
 }
 
 impl Parser {
@@ -15,9 +101,12 @@ impl Parser {
         Parser {
             tokens: tokens,
             current: 0,
+            errors: Vec::new(),
         }
     }
 
+    /// Discards tokens until the start of what is probably the next
+    /// statement, so one syntax error doesn't cascade into many.
     pub fn synchronize(&mut self) {
         self.advance();
 
@@ -42,120 +131,699 @@ impl Parser {
         }
     }
 
-    pub fn parse(&mut self) -> Result<Expression, Error> {
-        match self.expression() {
-            Ok(exp) => Ok(exp),
-            Err(e) => Err(e),
+    /// Parses the whole program, recovering after each syntax error so that
+    /// every error in the source is reported at once.
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, Vec<Error>> {
+        let mut statements = Vec::new();
+        while !self.is_at_end() {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
         }
-    }
 
-    fn expression(&mut self) -> Result<Expression, Error> {
-        self.equality()
+        if self.errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
     }
 
-    fn equality(&mut self) -> Result<Expression, Error> {
-        match self.comparison() {
-            Ok(mut expr) => {
-                while self.match_token(&[TokenType::BANG, TokenType::BANG_EQUAL]) {
-                    let operator = Operator::new(self.previous());
-                    let right = self.comparison()?;
-                    expr = Expression::Binary(Binary::new(expr, operator, right));
+    fn declaration(&mut self) -> Option<Stmt> {
+        match self.try_declaration() {
+            Ok(statement) => Some(statement),
+            Err(e) => {
+                if !self.at_lexical_error() {
+                    self.errors.push(e);
                 }
-                Ok(expr)
+                self.synchronize();
+                None
             }
-            Err(e) => Err(e),
         }
     }
 
-    fn comparison(&mut self) -> Result<Expression, Error> {
-        match self.term() {
-            Ok(mut expr) => {
-                while self.match_token(&[
-                    TokenType::GREATER,
-                    TokenType::GREATER_EQUAL,
-                    TokenType::LESS,
-                    TokenType::LESS_EQUAL,
-                ]) {
-                    let operator = Operator::new(self.previous());
-                    let right = self.term()?;
-                    expr = Expression::Binary(Binary::new(expr, operator, right));
+    /// Whether parsing stopped at a lexeme the scanner already reported, or
+    /// at the end of input right after one (an unterminated string or
+    /// comment swallows the rest of the source).
+    fn at_lexical_error(&self) -> bool {
+        let after_error = self.current > 0 && self.previous().token_type() == TokenType::ERROR;
+        self.peek().token_type() == TokenType::ERROR || (self.is_at_end() && after_error)
+    }
+
+    fn try_declaration(&mut self) -> Result<Stmt, Error> {
+        if self.match_token(&[TokenType::CLASS]) {
+            return self.class_declaration();
+        }
+        if self.match_token(&[TokenType::FUN]) {
+            return Ok(Stmt::Function(self.function("function")?));
+        }
+        if self.match_token(&[TokenType::VAR]) {
+            return self.var_declaration();
+        }
+
+        self.statement()
+    }
+
+    fn class_declaration(&mut self) -> Result<Stmt, Error> {
+        let start = self.previous();
+        let name = self.consume(TokenType::IDENTIFIER, "Expect class name.".to_string())?;
+
+        let mut superclass = None;
+        if self.match_token(&[TokenType::LESS]) {
+            self.consume(TokenType::IDENTIFIER, "Expect superclass name.".to_string())?;
+            superclass = Some(Variable::new(self.previous()));
+        }
+
+        self.consume(
+            TokenType::LEFT_BRACE,
+            "Expect '{' before class body.".to_string(),
+        )?;
+
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            methods.push(self.function("method")?);
+        }
+
+        self.consume(
+            TokenType::RIGHT_BRACE,
+            "Expect '}' after class body.".to_string(),
+        )?;
+
+        let span = self.span_from(&start);
+        Ok(Stmt::Class(Class::new(name, superclass, methods, span)))
+    }
+
+    fn function(&mut self, kind: &str) -> Result<Rc<Function>, Error> {
+        let name = self.consume(TokenType::IDENTIFIER, format!("Expect {} name.", kind))?;
+        self.consume(
+            TokenType::LEFT_PAREN,
+            format!("Expect '(' after {} name.", kind),
+        )?;
+
+        let mut params = Vec::new();
+        if !self.check(TokenType::RIGHT_PAREN) {
+            loop {
+                if params.len() >= MAX_ARGUMENTS {
+                    let error = self.scan_error(format!(
+                        "Can't have more than {} parameters.",
+                        MAX_ARGUMENTS
+                    ));
+                    self.errors.push(error);
+                }
+                params.push(
+                    self.consume(TokenType::IDENTIFIER, "Expect parameter name.".to_string())?,
+                );
+
+                if !self.match_token(&[TokenType::COMMA]) {
+                    break;
                 }
-                Ok(expr)
             }
-            Err(e) => Err(e),
         }
+        self.consume(
+            TokenType::RIGHT_PAREN,
+            "Expect ')' after parameters.".to_string(),
+        )?;
+
+        self.consume(
+            TokenType::LEFT_BRACE,
+            format!("Expect '{{' before {} body.", kind),
+        )?;
+        let body = self.block()?;
+
+        let span = self.span_from(&name);
+        Ok(Rc::new(Function::new(name, params, body, span)))
     }
 
-    fn term(&mut self) -> Result<Expression, Error> {
-        match self.factor() {
-            Ok(mut expr) => {
-                while self.match_token(&[TokenType::MINUS, TokenType::PLUS]) {
-                    let operator = Operator::new(self.previous());
-                    let right = self.factor()?;
-                    expr = Expression::Binary(Binary::new(expr, operator, right));
-                }
-                Ok(expr)
+    fn var_declaration(&mut self) -> Result<Stmt, Error> {
+        let start = self.previous();
+        let name = self.consume(TokenType::IDENTIFIER, "Expect variable name.".to_string())?;
+
+        let mut initializer = None;
+        if self.match_token(&[TokenType::EQUAL]) {
+            initializer = Some(self.expression()?);
+        }
+
+        self.consume(
+            TokenType::SEMICOLON,
+            "Expect ';' after variable declaration.".to_string(),
+        )?;
+        Ok(Stmt::Var(Var::new(
+            name,
+            initializer,
+            self.span_from(&start),
+        )))
+    }
+
+    fn statement(&mut self) -> Result<Stmt, Error> {
+        if self.match_token(&[TokenType::FOR]) {
+            return self.for_statement();
+        }
+        if self.match_token(&[TokenType::IF]) {
+            return self.if_statement();
+        }
+        if self.match_token(&[TokenType::PRINT]) {
+            return self.print_statement();
+        }
+        if self.match_token(&[TokenType::RETURN]) {
+            return self.return_statement();
+        }
+        if self.match_token(&[TokenType::WHILE]) {
+            return self.while_statement();
+        }
+        if self.match_token(&[TokenType::LEFT_BRACE]) {
+            let start = self.previous();
+            let statements = self.block()?;
+            return Ok(Stmt::Block(Block::new(statements, self.span_from(&start))));
+        }
+
+        self.expression_statement()
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, Error> {
+        let mut statements = Vec::new();
+
+        while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
             }
-            Err(e) => Err(e),
         }
+
+        self.consume(
+            TokenType::RIGHT_BRACE,
+            "Expect '}' after block.".to_string(),
+        )?;
+        Ok(statements)
+    }
+
+    /// `for` has no node of its own: it is desugared into the equivalent
+    /// `while` loop wrapped in blocks for the initializer and increment.
+    fn for_statement(&mut self) -> Result<Stmt, Error> {
+        let for_token = self.previous();
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'for'.".to_string())?;
+
+        let initializer = if self.match_token(&[TokenType::SEMICOLON]) {
+            None
+        } else if self.match_token(&[TokenType::VAR]) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let mut condition = None;
+        if !self.check(TokenType::SEMICOLON) {
+            condition = Some(self.expression()?);
+        }
+        self.consume(
+            TokenType::SEMICOLON,
+            "Expect ';' after loop condition.".to_string(),
+        )?;
+
+        let mut increment = None;
+        if !self.check(TokenType::RIGHT_PAREN) {
+            increment = Some(self.expression()?);
+        }
+        self.consume(
+            TokenType::RIGHT_PAREN,
+            "Expect ')' after for clauses.".to_string(),
+        )?;
+
+        let mut body = self.statement()?;
+        let span = self.span_from(&for_token);
+
+        if let Some(increment) = increment {
+            let increment_span = increment.span();
+            body = Stmt::Block(Block::new(
+                vec![
+                    body,
+                    Stmt::Expression(ExpressionStmt::new(increment, increment_span)),
+                ],
+                span,
+            ));
+        }
+
+        let condition = condition.unwrap_or_else(|| {
+            let token = Token::new(
+                "true".to_string(),
+                TokenType::TRUE,
+                for_token.line(),
+                for_token.column(),
+                for_token.offset(),
+                None,
+            );
+            Expression::LiteralExp(LiteralExp::new(token))
+        });
+        body = Stmt::While(While::new(condition, body, span));
+
+        if let Some(initializer) = initializer {
+            body = Stmt::Block(Block::new(vec![initializer, body], span));
+        }
+
+        Ok(body)
+    }
+
+    /// The `else` is bound to the nearest `if`, since the inner `if_statement`
+    /// call eagerly looks for it before returning.
+    fn if_statement(&mut self) -> Result<Stmt, Error> {
+        let start = self.previous();
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'if'.".to_string())?;
+        let condition = self.expression()?;
+        self.consume(
+            TokenType::RIGHT_PAREN,
+            "Expect ')' after if condition.".to_string(),
+        )?;
+
+        let then_branch = self.statement()?;
+        let mut else_branch = None;
+        if self.match_token(&[TokenType::ELSE]) {
+            else_branch = Some(self.statement()?);
+        }
+
+        let span = self.span_from(&start);
+        Ok(Stmt::If(If::new(condition, then_branch, else_branch, span)))
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, Error> {
+        let start = self.previous();
+        self.consume(
+            TokenType::LEFT_PAREN,
+            "Expect '(' after 'while'.".to_string(),
+        )?;
+        let condition = self.expression()?;
+        self.consume(
+            TokenType::RIGHT_PAREN,
+            "Expect ')' after condition.".to_string(),
+        )?;
+        let body = self.statement()?;
+
+        Ok(Stmt::While(While::new(
+            condition,
+            body,
+            self.span_from(&start),
+        )))
+    }
+
+    fn print_statement(&mut self) -> Result<Stmt, Error> {
+        let start = self.previous();
+        let value = self.expression()?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after value.".to_string())?;
+        Ok(Stmt::Print(Print::new(value, self.span_from(&start))))
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.previous();
+        let mut value = None;
+        if !self.check(TokenType::SEMICOLON) {
+            value = Some(self.expression()?);
+        }
+
+        self.consume(
+            TokenType::SEMICOLON,
+            "Expect ';' after return value.".to_string(),
+        )?;
+        let span = self.span_from(&keyword);
+        Ok(Stmt::Return(Return::new(keyword, value, span)))
     }
 
-    fn factor(&mut self) -> Result<Expression, Error> {
-        match self.unary() {
-            Ok(mut expr) => {
-                while self.match_token(&[TokenType::SLASH, TokenType::STAR]) {
-                    let operator = Operator::new(self.previous());
-                    let right = self.unary()?;
-                    expr = Expression::Binary(Binary::new(expr, operator, right));
+    fn expression_statement(&mut self) -> Result<Stmt, Error> {
+        let start = self.peek();
+        let expression = self.expression()?;
+        self.consume(
+            TokenType::SEMICOLON,
+            "Expect ';' after expression.".to_string(),
+        )?;
+        Ok(Stmt::Expression(ExpressionStmt::new(
+            expression,
+            self.span_from(&start),
+        )))
+    }
+
+    fn expression(&mut self) -> Result<Expression, Error> {
+        self.parse_precedence(Precedence::Comma)
+    }
+
+    /// Parses an expression whose operators all bind at least as tightly as
+    /// `precedence`: the prefix rule of the first token, then infix rules for
+    /// as long as the next token is an operator that binds tightly enough.
+    fn parse_precedence(&mut self, precedence: Precedence) -> Result<Expression, Error> {
+        let rule = Self::rule(self.peek().token_type());
+        let mut expr = match rule.prefix {
+            Some(prefix) => {
+                self.advance();
+                prefix(self)?
+            }
+            None => return self.missing_left_operand(&rule),
+        };
+
+        loop {
+            let rule = Self::rule(self.peek().token_type());
+            match rule.infix {
+                Some(infix) if precedence <= rule.precedence => {
+                    self.advance();
+                    expr = infix(self, expr)?;
                 }
-                Ok(expr)
+                _ => break,
             }
-            Err(e) => Err(e),
         }
+
+        Ok(expr)
     }
 
-    fn unary(&mut self) -> Result<Expression, Error> {
-        if self.match_token(&[TokenType::BANG, TokenType::MINUS]) {
-            let operator = self.previous();
-            match self.unary() {
-                Ok(right) => return Ok(Expression::Unary(Unary::new(operator, right))),
-                Err(e) => return Err(e),
+    /// The parse table. Every token maps to how it parses at the start of an
+    /// expression, how it parses after a left operand and how tightly it binds
+    /// in that position.
+    fn rule(token_type: TokenType) -> ParseRule {
+        use Associativity::{Left, Right};
+
+        let (prefix, infix, precedence, associativity): (
+            Option<PrefixRule>,
+            Option<InfixRule>,
+            Precedence,
+            Associativity,
+        ) = match token_type {
+            TokenType::LEFT_PAREN => (
+                Some(Self::grouping),
+                Some(Self::call),
+                Precedence::Call,
+                Left,
+            ),
+            TokenType::DOT => (None, Some(Self::get), Precedence::Call, Left),
+            TokenType::PLUS_PLUS => (
+                Some(|parser| parser.prefix_update(TokenType::PLUS)),
+                Some(|parser, target| parser.postfix_update(target, TokenType::PLUS)),
+                Precedence::Postfix,
+                Left,
+            ),
+            TokenType::MINUS_MINUS => (
+                Some(|parser| parser.prefix_update(TokenType::MINUS)),
+                Some(|parser, target| parser.postfix_update(target, TokenType::MINUS)),
+                Precedence::Postfix,
+                Left,
+            ),
+            TokenType::STAR_STAR => (None, Some(Self::binary), Precedence::Exponent, Right),
+            TokenType::BANG | TokenType::TILDE => (Some(Self::unary), None, Precedence::None, Left),
+            TokenType::STAR | TokenType::SLASH | TokenType::TILDE_SLASH | TokenType::PERCENT => {
+                (None, Some(Self::binary), Precedence::Factor, Left)
             }
-        }
+            TokenType::MINUS => (
+                Some(Self::unary),
+                Some(Self::binary),
+                Precedence::Term,
+                Left,
+            ),
+            TokenType::PLUS => (None, Some(Self::binary), Precedence::Term, Left),
+            TokenType::LESS_LESS | TokenType::GREATER_GREATER => {
+                (None, Some(Self::binary), Precedence::Shift, Left)
+            }
+            TokenType::AMPERSAND => (None, Some(Self::binary), Precedence::BitAnd, Left),
+            TokenType::CARET => (None, Some(Self::binary), Precedence::BitXor, Left),
+            TokenType::PIPE => (None, Some(Self::binary), Precedence::BitOr, Left),
+            TokenType::GREATER
+            | TokenType::GREATER_EQUAL
+            | TokenType::LESS
+            | TokenType::LESS_EQUAL => (None, Some(Self::binary), Precedence::Comparison, Left),
+            TokenType::BANG_EQUAL | TokenType::EQUAL_EQUAL => {
+                (None, Some(Self::binary), Precedence::Equality, Left)
+            }
+            TokenType::AND => (None, Some(Self::logical), Precedence::And, Left),
+            TokenType::OR => (None, Some(Self::logical), Precedence::Or, Left),
+            TokenType::QUESTION => (
+                None,
+                Some(Self::conditional),
+                Precedence::Conditional,
+                Right,
+            ),
+            TokenType::EQUAL => (None, Some(Self::assign), Precedence::Assignment, Right),
+            TokenType::PLUS_EQUAL => (
+                None,
+                Some(|parser, target| parser.compound_assignment(target, TokenType::PLUS)),
+                Precedence::Assignment,
+                Right,
+            ),
+            TokenType::MINUS_EQUAL => (
+                None,
+                Some(|parser, target| parser.compound_assignment(target, TokenType::MINUS)),
+                Precedence::Assignment,
+                Right,
+            ),
+            TokenType::STAR_EQUAL => (
+                None,
+                Some(|parser, target| parser.compound_assignment(target, TokenType::STAR)),
+                Precedence::Assignment,
+                Right,
+            ),
+            TokenType::SLASH_EQUAL => (
+                None,
+                Some(|parser, target| parser.compound_assignment(target, TokenType::SLASH)),
+                Precedence::Assignment,
+                Right,
+            ),
+            TokenType::PERCENT_EQUAL => (
+                None,
+                Some(|parser, target| parser.compound_assignment(target, TokenType::PERCENT)),
+                Precedence::Assignment,
+                Right,
+            ),
+            TokenType::COMMA => (None, Some(Self::binary), Precedence::Comma, Left),
+            TokenType::FALSE
+            | TokenType::TRUE
+            | TokenType::NIL
+            | TokenType::NUMBER
+            | TokenType::STRING
+            | TokenType::ERROR => (Some(Self::literal), None, Precedence::None, Left),
+            TokenType::INTERPOLATION => (Some(Self::interpolation), None, Precedence::None, Left),
+            TokenType::SUPER => (Some(Self::super_expression), None, Precedence::None, Left),
+            TokenType::THIS => (Some(Self::this), None, Precedence::None, Left),
+            TokenType::IDENTIFIER => (Some(Self::variable), None, Precedence::None, Left),
+            _ => (None, None, Precedence::None, Left),
+        };
 
-        self.primary()
+        ParseRule {
+            prefix: prefix,
+            infix: infix,
+            precedence: precedence,
+            associativity: associativity,
+        }
     }
 
-    fn primary(&mut self) -> Result<Expression, Error> {
-        if self.match_token(&[TokenType::FALSE]) {
-            return Ok(Expression::LiteralExp(LiteralExp::new(self.previous())));
+    /// Called when a token can't start an expression. For a binary operator
+    /// such as the `*` in `* 3` this is an error production: the right
+    /// operand is parsed and discarded so that parsing resumes after it.
+    fn missing_left_operand(&mut self, rule: &ParseRule) -> Result<Expression, Error> {
+        if rule.infix.is_none()
+            || !(Precedence::Or..=Precedence::Exponent).contains(&rule.precedence)
+        {
+            return Err(self.scan_error("No primary expression matched.".to_string()));
         }
-        if self.match_token(&[TokenType::TRUE]) {
-            return Ok(Expression::LiteralExp(LiteralExp::new(self.previous())));
+
+        let operator = self.advance();
+        self.parse_precedence(rule.operand_precedence())?;
+        Err(Error::at(
+            &operator,
+            format!(
+                "Binary operator '{}' is missing a left-hand operand.",
+                operator.lexeme()
+            ),
+        ))
+    }
+
+    fn binary(&mut self, left: Expression) -> Result<Expression, Error> {
+        let operator = self.previous();
+        let right =
+            self.parse_precedence(Self::rule(operator.token_type()).operand_precedence())?;
+        Ok(Expression::Binary(Binary::new(
+            left,
+            Operator::new(operator),
+            right,
+        )))
+    }
+
+    fn logical(&mut self, left: Expression) -> Result<Expression, Error> {
+        let operator = self.previous();
+        let right =
+            self.parse_precedence(Self::rule(operator.token_type()).operand_precedence())?;
+        Ok(Expression::Logical(Logical::new(left, operator, right)))
+    }
+
+    /// The middle operand may be any expression since it is delimited by `?`
+    /// and `:`. The last one may be an assignment, as in C, so
+    /// `a ? b : c = d` assigns to `c`.
+    fn conditional(&mut self, condition: Expression) -> Result<Expression, Error> {
+        let then_branch = self.expression()?;
+        self.consume(
+            TokenType::COLON,
+            "Expect ':' after then branch of conditional expression.".to_string(),
+        )?;
+        let else_branch = self.parse_precedence(Precedence::Assignment)?;
+        Ok(Expression::Conditional(Conditional::new(
+            condition,
+            then_branch,
+            else_branch,
+        )))
+    }
+
+    fn assign(&mut self, target: Expression) -> Result<Expression, Error> {
+        let equals = self.previous();
+        let value = self.parse_precedence(Precedence::Assignment)?;
+
+        match target {
+            Expression::Variable(variable) => {
+                let name = variable.name().clone();
+                return Ok(Expression::Assign(Assign::new(name, value)));
+            }
+            Expression::Get(get) => {
+                let (object, name) = get.into_parts();
+                return Ok(Expression::Set(Set::new(object, name, value)));
+            }
+            target => {
+                // The parser isn't confused here, so report without unwinding.
+                self.errors
+                    .push(Error::at(&equals, "Invalid assignment target.".to_string()));
+                return Ok(target);
+            }
         }
-        if self.match_token(&[TokenType::NIL]) {
-            return Ok(Expression::LiteralExp(LiteralExp::new(self.previous())));
+    }
+
+    /// `binary` is the operator the parse table pairs with the token, e.g.
+    /// `+` for `+=`.
+    fn compound_assignment(
+        &mut self,
+        target: Expression,
+        binary: TokenType,
+    ) -> Result<Expression, Error> {
+        let operator = self.previous();
+        let value = self.parse_precedence(Precedence::Assignment)?;
+        self.check_update_target(&target, &operator);
+        Ok(Expression::Update(Update::compound(
+            target, operator, binary, value,
+        )))
+    }
+
+    /// Compound assignments and `++`/`--` only apply to variables and fields.
+    fn check_update_target(&mut self, target: &Expression, operator: &Token) {
+        if !matches!(target, Expression::Variable(_) | Expression::Get(_)) {
+            let message = format!("Invalid target for '{}'.", operator.lexeme());
+            self.errors.push(Error::at(operator, message));
         }
+    }
 
-        if self.match_token(&[TokenType::NUMBER, TokenType::STRING]) {
-            return Ok(Expression::LiteralExp(LiteralExp::new(self.previous())));
+    fn unary(&mut self) -> Result<Expression, Error> {
+        let operator = self.previous();
+        let right = self.parse_precedence(Precedence::Unary)?;
+        Ok(Expression::Unary(Unary::new(operator, right)))
+    }
+
+    /// The operand of a prefix `++`/`--` stops before any operator looser
+    /// than a call, so `++a.b ** 2` increments `a.b`.
+    fn prefix_update(&mut self, binary: TokenType) -> Result<Expression, Error> {
+        let operator = self.previous();
+        let target = self.parse_precedence(Precedence::Call)?;
+        self.check_update_target(&target, &operator);
+        Ok(Expression::Update(Update::prefix(operator, binary, target)))
+    }
+
+    fn postfix_update(
+        &mut self,
+        target: Expression,
+        binary: TokenType,
+    ) -> Result<Expression, Error> {
+        let operator = self.previous();
+        self.check_update_target(&target, &operator);
+        Ok(Expression::Update(Update::postfix(
+            target, operator, binary,
+        )))
+    }
+
+    fn call(&mut self, callee: Expression) -> Result<Expression, Error> {
+        let mut arguments = Vec::new();
+        if !self.check(TokenType::RIGHT_PAREN) {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
+                    let error = self
+                        .scan_error(format!("Can't have more than {} arguments.", MAX_ARGUMENTS));
+                    self.errors.push(error);
+                }
+                // Commas here separate arguments, not comma expressions.
+                arguments.push(self.parse_precedence(Precedence::Assignment)?);
+
+                if !self.match_token(&[TokenType::COMMA]) {
+                    break;
+                }
+            }
         }
 
-        if self.match_token(&[TokenType::LEFT_PAREN]) {
-            let expression = self.expression()?;
-            match self.consume(
-                TokenType::RIGHT_PAREN,
-                "Expect ')' after expression.".to_string(),
-            ) {
-                Ok(_) => return Ok(Expression::Grouping(Grouping::new(expression))),
-                Err(e) => return Err(e),
+        let paren = self.consume(
+            TokenType::RIGHT_PAREN,
+            "Expect ')' after arguments.".to_string(),
+        )?;
+
+        Ok(Expression::Call(Call::new(callee, paren, arguments)))
+    }
+
+    fn get(&mut self, object: Expression) -> Result<Expression, Error> {
+        let name = self.consume(
+            TokenType::IDENTIFIER,
+            "Expect property name after '.'.".to_string(),
+        )?;
+        Ok(Expression::Get(Get::new(object, name)))
+    }
+
+    fn grouping(&mut self) -> Result<Expression, Error> {
+        let left_paren = self.previous();
+        let expression = self.expression()?;
+        self.consume(
+            TokenType::RIGHT_PAREN,
+            "Expect ')' after expression.".to_string(),
+        )?;
+        let span = self.span_from(&left_paren);
+        Ok(Expression::Grouping(Grouping::new(expression, span)))
+    }
+
+    fn literal(&mut self) -> Result<Expression, Error> {
+        Ok(Expression::LiteralExp(LiteralExp::new(self.previous())))
+    }
+
+    fn variable(&mut self) -> Result<Expression, Error> {
+        Ok(Expression::Variable(Variable::new(self.previous())))
+    }
+
+    fn this(&mut self) -> Result<Expression, Error> {
+        Ok(Expression::This(This::new(self.previous())))
+    }
+
+    fn super_expression(&mut self) -> Result<Expression, Error> {
+        let keyword = self.previous();
+        self.consume(TokenType::DOT, "Expect '.' after 'super'.".to_string())?;
+        let method = self.consume(
+            TokenType::IDENTIFIER,
+            "Expect superclass method name.".to_string(),
+        )?;
+        Ok(Expression::Super(Super::new(keyword, method)))
+    }
+
+    /// Parses the rest of an interpolated string whose first INTERPOLATION
+    /// segment has just been consumed.
+    fn interpolation(&mut self) -> Result<Expression, Error> {
+        let start = self.previous();
+        let mut parts = vec![Expression::LiteralExp(LiteralExp::new(start.clone()))];
+
+        loop {
+            parts.push(self.expression()?);
+
+            if self.match_token(&[TokenType::INTERPOLATION]) {
+                parts.push(Expression::LiteralExp(LiteralExp::new(self.previous())));
+            } else {
+                let end = self.consume(
+                    TokenType::STRING,
+                    "Expect '}' after interpolated expression.".to_string(),
+                )?;
+                parts.push(Expression::LiteralExp(LiteralExp::new(end)));
+                break;
             }
-        } else {
-            return Err(Error::new(
-                self.peek().line(),
-                "No primary expression matched.".to_string(),
-            ));
         }
+
+        Ok(Expression::Interpolation(Interpolation::new(
+            parts,
+            self.span_from(&start),
+        )))
     }
 
     fn match_token(&mut self, types: &[TokenType]) -> bool {
@@ -219,8 +887,288 @@ impl Parser {
         }
     }
 
+    /// The span from `start` up to and including the last consumed token.
+    fn span_from(&self, start: &Token) -> Span {
+        start.span().to(self.previous().span())
+    }
+
+    /// An error at the next token. At the end of input it points just past
+    /// the last token instead, rather than at whatever blank lines follow.
     fn scan_error(&self, message: String) -> Error {
-        let line = self.peek().line();
-        Error::new(line, message)
+        if self.is_at_end() && self.current > 0 {
+            let previous = self.previous();
+            let (line, column) = previous.end();
+            let end = previous.span().end();
+            return Error::new(line, column, Span::new(end, end), message);
+        }
+        Error::at(&self.peek(), message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ast_printer::AstPrinter, scanner::Scanner};
+
+    /// Parses a single expression statement and renders it fully parenthesized.
+    fn parse(source: &str) -> String {
+        let tokens = Scanner::new(format!("{};", source)).scan_tokens();
+        let statements = Parser::new(tokens).parse().expect("source should parse");
+        match &statements[..] {
+            [Stmt::Expression(statement)] => AstPrinter::new().print(statement.expression()),
+            _ => panic!("expected a single expression statement"),
+        }
+    }
+
+    fn errors(source: &str) -> Vec<(usize, usize, String)> {
+        let tokens = Scanner::new(source.to_string()).scan_tokens();
+        Error::summarize(&Parser::new(tokens).parse().unwrap_err())
+    }
+
+    #[test]
+    fn binary_operator_without_left_operand_is_reported_at_the_operator() {
+        assert_eq!(
+            errors("print 1;\n  * 3 + 4;\nprint == x;"),
+            [
+                (
+                    2,
+                    3,
+                    "Binary operator '*' is missing a left-hand operand.".to_string()
+                ),
+                (
+                    3,
+                    7,
+                    "Binary operator '==' is missing a left-hand operand.".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn lexemes_the_scanner_rejected_are_not_reported_again() {
+        let sources = [
+            "print @;",
+            "print 0x;",
+            "print \"abc",
+            "print \"a ${b} c",
+            "print 1 /* x",
+            "var @ = 1;",
+        ];
+        for source in sources {
+            let mut scanner = Scanner::new(source.to_string());
+            let tokens = scanner.scan_tokens();
+            assert_eq!(scanner.errors().len(), 1, "{}", source);
+            assert!(Parser::new(tokens).parse().is_ok(), "{}", source);
+        }
+
+        assert_eq!(
+            errors("print 1 @ 2;\nprint 2 +;"),
+            [(2, 10, "No primary expression matched.".to_string())]
+        );
+    }
+
+    #[test]
+    fn comma_binds_loosest() {
+        assert_eq!(parse("a = b, c += d"), "(, (= a b) (+= c d))");
+        assert_eq!(parse("a, b, c"), "(, (, a b) c)");
+    }
+
+    #[test]
+    fn assignment_binds_looser_than_conditional() {
+        assert_eq!(parse("a = b ? c : d"), "(= a (?: b c d))");
+    }
+
+    #[test]
+    fn else_branch_of_conditional_may_be_an_assignment() {
+        assert_eq!(parse("a ? b : c = d"), "(?: a b (= c d))");
+        assert_eq!(parse("a ? b : c += d"), "(?: a b (+= c d))");
+        assert_eq!(parse("a ? b : c = d, e"), "(, (?: a b (= c d)) e)");
+    }
+
+    #[test]
+    fn conditional_is_right_associative() {
+        assert_eq!(parse("a ? b : c ? d : e"), "(?: a b (?: c d e))");
+        assert_eq!(parse("a ? b ? c : d : e"), "(?: a (?: b c d) e)");
+    }
+
+    #[test]
+    fn conditional_binds_looser_than_logical_operators() {
+        assert_eq!(
+            parse("a or b ? c, d : e and f"),
+            "(?: (or a b) (, c d) (and e f))"
+        );
+    }
+
+    #[test]
+    fn logical_operators_bind_looser_than_equality() {
+        assert_eq!(parse("a or b and c == d"), "(or a (and b (== c d)))");
+    }
+
+    #[test]
+    fn comparison_binds_looser_than_bitwise_operators() {
+        assert_eq!(parse("a == b < c | d"), "(== a (< b (| c d)))");
+    }
+
+    #[test]
+    fn bitwise_operators_bind_or_xor_and_from_loosest() {
+        assert_eq!(parse("a | b ^ c & d"), "(| a (^ b (& c d)))");
+    }
+
+    #[test]
+    fn shifts_bind_tighter_than_bitwise_and_looser_than_terms() {
+        assert_eq!(parse("a & b << c + d"), "(& a (<< b (+ c d)))");
+    }
+
+    #[test]
+    fn factors_bind_tighter_than_terms() {
+        assert_eq!(parse("a + b * c - d % e"), "(- (+ a (* b c)) (% d e))");
+        assert_eq!(parse("a / b ~/ c"), "(~/ (/ a b) c)");
+    }
+
+    #[test]
+    fn tilde_slash_parses_as_floor_division() {
+        assert_eq!(parse("a ~/ b"), "(~/ a b)");
+        assert_eq!(parse("-a ~/ b ~/ c"), "(~/ (~/ (- a) b) c)");
+        assert_eq!(parse("~a ~/ b"), "(~/ (~ a) b)");
+    }
+
+    #[test]
+    fn exponent_binds_tighter_than_unary_on_its_left() {
+        assert_eq!(parse("-a ** b"), "(- (** a b))");
+        assert_eq!(parse("~a ** -b"), "(~ (** a (- b)))");
+    }
+
+    #[test]
+    fn exponent_is_right_associative() {
+        assert_eq!(parse("a ** b ** c"), "(** a (** b c))");
+    }
+
+    #[test]
+    fn increments_bind_tighter_than_exponent() {
+        assert_eq!(parse("-a++ ** b"), "(- (** (postfix ++ a) b))");
+        assert_eq!(parse("--a.b ** c"), "(** (-- (. a b)) c)");
+    }
+
+    #[test]
+    fn compound_assignment_is_right_associative() {
+        assert_eq!(parse("a += b -= c * d"), "(+= a (-= b (* c d)))");
+    }
+
+    #[test]
+    fn binary_operators_are_left_associative() {
+        assert_eq!(parse("a - b - c"), "(- (- a b) c)");
+        assert_eq!(parse("a << b >> c"), "(>> (<< a b) c)");
+    }
+
+    #[test]
+    fn grouping_overrides_precedence() {
+        assert_eq!(parse("(a + b) * c"), "(* (group (+ a b)) c)");
+    }
+
+    #[test]
+    fn interpolation_alternates_segments_and_expressions() {
+        assert_eq!(
+            parse(r#""a ${b + 1} c ${d}""#),
+            r#"(interpolate "a " (+ b 1) " c " d "")"#
+        );
+        assert_eq!(
+            parse(r#""x ${ "y ${z}" } w""#),
+            r#"(interpolate "x " (interpolate "y " z "") " w")"#
+        );
+    }
+
+    #[test]
+    fn unterminated_interpolation_is_reported_at_end_of_input() {
+        assert_eq!(
+            errors("print \"a ${b"),
+            [(
+                1,
+                13,
+                "Expect '}' after interpolated expression.".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn every_infix_rule_parses_between_two_operands_without_panicking() {
+        use TokenType::*;
+        let token_types = [
+            LEFT_PAREN,
+            RIGHT_PAREN,
+            LEFT_BRACE,
+            RIGHT_BRACE,
+            COMMA,
+            DOT,
+            MINUS,
+            PLUS,
+            SEMICOLON,
+            SLASH,
+            STAR,
+            PERCENT,
+            AMPERSAND,
+            PIPE,
+            CARET,
+            QUESTION,
+            COLON,
+            BANG,
+            BANG_EQUAL,
+            EQUAL,
+            EQUAL_EQUAL,
+            GREATER,
+            GREATER_EQUAL,
+            LESS,
+            LESS_EQUAL,
+            LESS_LESS,
+            GREATER_GREATER,
+            STAR_STAR,
+            TILDE,
+            TILDE_SLASH,
+            PLUS_EQUAL,
+            PLUS_PLUS,
+            MINUS_EQUAL,
+            MINUS_MINUS,
+            STAR_EQUAL,
+            SLASH_EQUAL,
+            PERCENT_EQUAL,
+            IDENTIFIER,
+            STRING,
+            INTERPOLATION,
+            NUMBER,
+            AND,
+            CLASS,
+            ELSE,
+            FALSE,
+            FUN,
+            FOR,
+            IF,
+            NIL,
+            OR,
+            PRINT,
+            RETURN,
+            SUPER,
+            THIS,
+            TRUE,
+            VAR,
+            WHILE,
+            ERROR,
+            EOF,
+        ];
+        let token = |lexeme: &str, token_type, column| {
+            Token::new(lexeme.to_string(), token_type, 1, column, column - 1, None)
+        };
+        for token_type in token_types {
+            if Parser::rule(token_type).infix.is_none() {
+                continue;
+            }
+            let tokens = vec![
+                token("a", IDENTIFIER, 1),
+                token(&token_type.to_string(), token_type, 2),
+                token("b", IDENTIFIER, 3),
+                token(";", SEMICOLON, 4),
+                token("", EOF, 5),
+            ];
+            // Operators that need more than `a op b` report an error instead.
+            let _ = Parser::new(tokens).parse();
+        }
     }
 }
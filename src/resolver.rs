@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+
+use crate::{
+    error::Error,
+    expression::Expression,
+    statement::{Class, Function, Stmt},
+    token::Token,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+/// Static pass run between parsing and interpretation. It records on every
+/// variable reference how many scopes away its declaration lives, and
+/// reports scope errors the parser's grammar cannot catch.
+pub struct Resolver {
+    scopes: Vec<HashMap<String, bool>>,
+    current_function: FunctionType,
+    current_class: ClassType,
+    errors: Vec<Error>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Resolver {
+            scopes: Vec::new(),
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn resolve(mut self, statements: &[Stmt]) -> Result<(), Vec<Error>> {
+        self.resolve_statements(statements);
+
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    fn resolve_statements(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            self.resolve_statement(statement);
+        }
+    }
+
+    fn resolve_statement(&mut self, statement: &Stmt) {
+        match statement {
+            Stmt::Expression(statement) => self.resolve_expression(statement.expression()),
+            Stmt::Print(statement) => self.resolve_expression(statement.expression()),
+            Stmt::Var(statement) => {
+                self.declare(statement.name());
+                if let Some(initializer) = statement.initializer() {
+                    self.resolve_expression(initializer);
+                }
+                self.define(statement.name());
+            }
+            Stmt::Block(statement) => {
+                self.begin_scope();
+                self.resolve_statements(statement.statements());
+                self.end_scope();
+            }
+            Stmt::If(statement) => {
+                self.resolve_expression(statement.condition());
+                self.resolve_statement(statement.then_branch());
+                if let Some(else_branch) = statement.else_branch() {
+                    self.resolve_statement(else_branch);
+                }
+            }
+            Stmt::While(statement) => {
+                self.resolve_expression(statement.condition());
+                self.resolve_statement(statement.body());
+            }
+            Stmt::Function(function) => {
+                self.declare(function.name());
+                self.define(function.name());
+                self.resolve_function(function, FunctionType::Function);
+            }
+            Stmt::Return(statement) => {
+                if self.current_function == FunctionType::None {
+                    self.error(statement.keyword(), "Can't return from top-level code.");
+                }
+                if let Some(value) = statement.value() {
+                    if self.current_function == FunctionType::Initializer {
+                        self.error(
+                            statement.keyword(),
+                            "Can't return a value from an initializer.",
+                        );
+                    }
+                    self.resolve_expression(value);
+                }
+            }
+            Stmt::Class(class) => self.resolve_class(class),
+        }
+    }
+
+    fn resolve_class(&mut self, class: &Class) {
+        let enclosing_class = self.current_class;
+        self.current_class = ClassType::Class;
+
+        self.declare(class.name());
+        self.define(class.name());
+
+        if let Some(superclass) = class.superclass() {
+            if superclass.name().lexeme() == class.name().lexeme() {
+                self.error(superclass.name(), "A class can't inherit from itself.");
+            }
+
+            self.current_class = ClassType::Subclass;
+            if let Some(depth) = self.resolve_local(superclass.name()) {
+                superclass.resolve(depth);
+            }
+
+            self.begin_scope();
+            self.define_name("super");
+        }
+
+        self.begin_scope();
+        self.define_name("this");
+
+        for method in class.methods() {
+            let function_type = if method.name().lexeme() == "init" {
+                FunctionType::Initializer
+            } else {
+                FunctionType::Method
+            };
+            self.resolve_function(method, function_type);
+        }
+
+        self.end_scope();
+        if class.superclass().is_some() {
+            self.end_scope();
+        }
+
+        self.current_class = enclosing_class;
+    }
+
+    fn resolve_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::LiteralExp(_) => (),
+            Expression::Grouping(grouping) => self.resolve_expression(grouping.expression()),
+            Expression::Unary(unary) => self.resolve_expression(unary.expression()),
+            Expression::Binary(binary) => {
+                self.resolve_expression(binary.left());
+                self.resolve_expression(binary.right());
+            }
+            Expression::Logical(logical) => {
+                self.resolve_expression(logical.left());
+                self.resolve_expression(logical.right());
+            }
+            Expression::Conditional(conditional) => {
+                self.resolve_expression(conditional.condition());
+                self.resolve_expression(conditional.then_branch());
+                self.resolve_expression(conditional.else_branch());
+            }
+            Expression::Call(call) => {
+                self.resolve_expression(call.callee());
+                for argument in call.arguments() {
+                    self.resolve_expression(argument);
+                }
+            }
+            Expression::Variable(variable) => {
+                let name = variable.name();
+                if let Some(scope) = self.scopes.last() {
+                    if scope.get(&name.lexeme()) == Some(&false) {
+                        self.error(name, "Can't read local variable in its own initializer.");
+                    }
+                }
+                if let Some(depth) = self.resolve_local(name) {
+                    variable.resolve(depth);
+                }
+            }
+            Expression::Assign(assign) => {
+                self.resolve_expression(assign.value());
+                if let Some(depth) = self.resolve_local(assign.name()) {
+                    assign.resolve(depth);
+                }
+            }
+            Expression::Get(get) => self.resolve_expression(get.object()),
+            Expression::Interpolation(interpolation) => {
+                for part in interpolation.parts() {
+                    self.resolve_expression(part);
+                }
+            }
+            Expression::Update(update) => {
+                if let Some(value) = update.value() {
+                    self.resolve_expression(value);
+                }
+                self.resolve_expression(update.target());
+            }
+            Expression::Set(set) => {
+                self.resolve_expression(set.value());
+                self.resolve_expression(set.object());
+            }
+            Expression::Super(expression) => {
+                match self.current_class {
+                    ClassType::None => self.error(
+                        expression.keyword(),
+                        "Can't use 'super' outside of a class.",
+                    ),
+                    ClassType::Class => self.error(
+                        expression.keyword(),
+                        "Can't use 'super' in a class with no superclass.",
+                    ),
+                    ClassType::Subclass => (),
+                }
+                if let Some(depth) = self.resolve_local(expression.keyword()) {
+                    expression.resolve(depth);
+                }
+            }
+            Expression::This(this) => {
+                if self.current_class == ClassType::None {
+                    self.error(this.keyword(), "Can't use 'this' outside of a class.");
+                    return;
+                }
+                if let Some(depth) = self.resolve_local(this.keyword()) {
+                    this.resolve(depth);
+                }
+            }
+        }
+    }
+
+    fn resolve_function(&mut self, function: &Function, function_type: FunctionType) {
+        let enclosing_function = self.current_function;
+        self.current_function = function_type;
+
+        self.begin_scope();
+        for param in function.params() {
+            self.declare(param);
+            self.define(param);
+        }
+        self.resolve_statements(function.body());
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+    }
+
+    /// Returns how many scopes up `name` is declared, or `None` if it is
+    /// not found in any local scope and must therefore be a global.
+    fn resolve_local(&self, name: &Token) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .position(|scope| scope.contains_key(&name.lexeme()))
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token) {
+        let already_declared = match self.scopes.last_mut() {
+            Some(scope) => scope.insert(name.lexeme(), false).is_some(),
+            None => false,
+        };
+
+        if already_declared {
+            self.error(name, "Already a variable with this name in this scope.");
+        }
+    }
+
+    fn define(&mut self, name: &Token) {
+        self.define_name(&name.lexeme());
+    }
+
+    fn define_name(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), true);
+        }
+    }
+
+    fn error(&mut self, token: &Token, message: &str) {
+        self.errors.push(Error::at(token, message.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parser::Parser, scanner::Scanner};
+
+    fn parse(source: &str) -> Vec<Stmt> {
+        let tokens = Scanner::new(source.to_string()).scan_tokens();
+        Parser::new(tokens).parse().expect("source should parse")
+    }
+
+    fn errors(source: &str) -> Vec<(usize, usize, String)> {
+        match Resolver::new().resolve(&parse(source)) {
+            Ok(()) => vec![],
+            Err(errors) => Error::summarize(&errors),
+        }
+    }
+
+    /// Collects the depth of every `print <variable>;` in source order.
+    fn printed_depths(statements: &[Stmt], depths: &mut Vec<Option<usize>>) {
+        for statement in statements {
+            match statement {
+                Stmt::Print(print) => {
+                    if let Expression::Variable(variable) = print.expression() {
+                        depths.push(variable.depth());
+                    }
+                }
+                Stmt::Block(block) => printed_depths(block.statements(), depths),
+                Stmt::Function(function) => printed_depths(function.body(), depths),
+                _ => (),
+            }
+        }
+    }
+
+    fn resolved_depths(source: &str) -> Vec<Option<usize>> {
+        let statements = parse(source);
+        Resolver::new()
+            .resolve(&statements)
+            .expect("source should resolve");
+        let mut depths = Vec::new();
+        printed_depths(&statements, &mut depths);
+        depths
+    }
+
+    #[test]
+    fn closures_bind_to_the_variable_in_scope_where_they_are_declared() {
+        let source = r#"
+            var a = "global";
+            {
+              fun show() { print a; }
+              show();
+              var a = "block";
+              show();
+            }
+        "#;
+        // `a` inside `show` is the global even after the block declares its own.
+        assert_eq!(resolved_depths(source), [None]);
+    }
+
+    #[test]
+    fn locals_resolve_to_the_number_of_scopes_between_use_and_declaration() {
+        let source = "{ var a = 1; fun f() { print a; { print a; var a = 2; print a; } } }";
+        assert_eq!(resolved_depths(source), [Some(1), Some(2), Some(0)]);
+    }
+
+    #[test]
+    fn reports_reading_a_local_in_its_own_initializer() {
+        assert_eq!(
+            errors("var a = 1;\n{ var a = a; }"),
+            [(
+                2,
+                11,
+                "Can't read local variable in its own initializer.".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_redeclaring_a_local() {
+        assert_eq!(
+            errors("fun f(x) {\n  var x = 1;\n}"),
+            [(
+                2,
+                7,
+                "Already a variable with this name in this scope.".to_string()
+            )]
+        );
+        assert_eq!(errors("var a = 1; var a = 2;"), []);
+    }
+
+    #[test]
+    fn reports_return_at_top_level() {
+        assert_eq!(
+            errors("print 1;\n  return 1;"),
+            [(2, 3, "Can't return from top-level code.".to_string())]
+        );
+    }
+
+    #[test]
+    fn reports_this_outside_a_class() {
+        assert_eq!(
+            errors("fun f() { return this; }"),
+            [(1, 18, "Can't use 'this' outside of a class.".to_string())]
+        );
+    }
+}
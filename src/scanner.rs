@@ -1,5 +1,7 @@
 use crate::{
+    bigint::BigInt,
     error::Error,
+    span::Span,
     token::{Literal, Token},
     token_type::TokenType,
 };
@@ -12,7 +14,11 @@ pub struct Scanner {
     start: usize,
     current: usize,
     line: usize,
-    pub error: Option<Error>,
+    column: usize,
+    start_line: usize,
+    start_column: usize,
+    interpolations: Vec<usize>,
+    errors: Vec<Error>,
     keywords: HashMap<String, TokenType>,
 }
 
@@ -42,7 +48,11 @@ impl Scanner {
             start: 0,
             current: 0,
             line: 1,
-            error: None,
+            column: 1,
+            start_line: 1,
+            start_column: 1,
+            interpolations: Vec::new(),
+            errors: Vec::new(),
             keywords: keywords,
         }
     }
@@ -50,6 +60,8 @@ impl Scanner {
     pub fn scan_tokens(&mut self) -> Vec<Token> {
         while !self.is_at_end() {
             self.start = self.current;
+            self.start_line = self.line;
+            self.start_column = self.column;
             self.scan_token();
         }
 
@@ -57,12 +69,19 @@ impl Scanner {
             String::from(""),
             TokenType::EOF,
             self.line,
+            self.column,
+            self.current,
             None,
         ));
 
         self.tokens.clone()
     }
 
+    /// Every lexical error found by `scan_tokens`, in source order.
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
     fn is_at_end(&self) -> bool {
         if self.current >= self.source.len() {
             return true;
@@ -71,54 +90,112 @@ impl Scanner {
         false
     }
 
+    /// Decodes the character starting at byte offset `position`, which must
+    /// lie on a char boundary. Returns `'\0'` past the end of the source.
     fn char_at(&self, position: usize) -> char {
-        self.source.as_bytes()[position] as char
+        self.source[position..].chars().next().unwrap_or('\0')
     }
 
     fn substring(&self, start: usize, end: usize) -> String {
-        self.source[self.start..self.current].to_string()
+        self.source[start..end].to_string()
     }
 
     fn scan_token(&mut self) {
         let c: char = self.advance();
 
         match c {
-            '(' => self.addToken(TokenType::LEFT_PAREN, None),
-            ')' => self.addToken(TokenType::RIGHT_PAREN, None),
-            '{' => self.addToken(TokenType::LEFT_BRACE, None),
-            '}' => self.addToken(TokenType::RIGHT_BRACE, None),
-            ',' => self.addToken(TokenType::COMMA, None),
-            '.' => self.addToken(TokenType::DOT, None),
-            '-' => self.addToken(TokenType::MINUS, None),
-            '+' => self.addToken(TokenType::PLUS, None),
-            '*' => self.addToken(TokenType::STAR, None),
-            ';' => self.addToken(TokenType::SEMICOLON, None),
+            '(' => self.add_token(TokenType::LEFT_PAREN, None),
+            ')' => self.add_token(TokenType::RIGHT_PAREN, None),
+            '{' => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+                self.add_token(TokenType::LEFT_BRACE, None);
+            }
+            '}' => match self.interpolations.last_mut() {
+                Some(0) => {
+                    self.interpolations.pop();
+                    self.string();
+                }
+                Some(depth) => {
+                    *depth -= 1;
+                    self.add_token(TokenType::RIGHT_BRACE, None);
+                }
+                None => self.add_token(TokenType::RIGHT_BRACE, None),
+            },
+            ',' => self.add_token(TokenType::COMMA, None),
+            '.' => self.add_token(TokenType::DOT, None),
+            '-' => {
+                if self.advance_if_match('-') {
+                    self.add_token(TokenType::MINUS_MINUS, None);
+                } else if self.advance_if_match('=') {
+                    self.add_token(TokenType::MINUS_EQUAL, None);
+                } else {
+                    self.add_token(TokenType::MINUS, None);
+                }
+            }
+            '+' => {
+                if self.advance_if_match('+') {
+                    self.add_token(TokenType::PLUS_PLUS, None);
+                } else if self.advance_if_match('=') {
+                    self.add_token(TokenType::PLUS_EQUAL, None);
+                } else {
+                    self.add_token(TokenType::PLUS, None);
+                }
+            }
+            '*' => {
+                if self.advance_if_match('*') {
+                    self.add_token(TokenType::STAR_STAR, None);
+                } else if self.advance_if_match('=') {
+                    self.add_token(TokenType::STAR_EQUAL, None);
+                } else {
+                    self.add_token(TokenType::STAR, None);
+                }
+            }
+            '%' => {
+                if self.advance_if_match('=') {
+                    self.add_token(TokenType::PERCENT_EQUAL, None);
+                } else {
+                    self.add_token(TokenType::PERCENT, None);
+                }
+            }
+            '&' => self.add_token(TokenType::AMPERSAND, None),
+            '|' => self.add_token(TokenType::PIPE, None),
+            '^' => self.add_token(TokenType::CARET, None),
+            '?' => self.add_token(TokenType::QUESTION, None),
+            ':' => self.add_token(TokenType::COLON, None),
+            ';' => self.add_token(TokenType::SEMICOLON, None),
+            '"' => self.string(),
             '!' => {
                 if self.advance_if_match('=') {
-                    self.addToken(TokenType::BANG_EQUAL, None);
+                    self.add_token(TokenType::BANG_EQUAL, None);
                 } else {
-                    self.addToken(TokenType::BANG, None);
+                    self.add_token(TokenType::BANG, None);
                 }
             }
             '=' => {
                 if self.advance_if_match('=') {
-                    self.addToken(TokenType::EQUAL_EQUAL, None);
+                    self.add_token(TokenType::EQUAL_EQUAL, None);
                 } else {
-                    self.addToken(TokenType::EQUAL, None);
+                    self.add_token(TokenType::EQUAL, None);
                 }
             }
             '<' => {
                 if self.advance_if_match('=') {
-                    self.addToken(TokenType::LESS_EQUAL, None);
+                    self.add_token(TokenType::LESS_EQUAL, None);
+                } else if self.advance_if_match('<') {
+                    self.add_token(TokenType::LESS_LESS, None);
                 } else {
-                    self.addToken(TokenType::LESS, None);
+                    self.add_token(TokenType::LESS, None);
                 }
             }
             '>' => {
                 if self.advance_if_match('=') {
-                    self.addToken(TokenType::GREATER_EQUAL, None);
+                    self.add_token(TokenType::GREATER_EQUAL, None);
+                } else if self.advance_if_match('>') {
+                    self.add_token(TokenType::GREATER_GREATER, None);
                 } else {
-                    self.addToken(TokenType::GREATER, None);
+                    self.add_token(TokenType::GREATER, None);
                 }
             }
             '/' => {
@@ -126,45 +203,97 @@ impl Scanner {
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
+                } else if self.advance_if_match('*') {
+                    self.block_comment();
+                } else if self.advance_if_match('=') {
+                    self.add_token(TokenType::SLASH_EQUAL, None);
                 } else {
-                    self.addToken(TokenType::SLASH, None);
+                    self.add_token(TokenType::SLASH, None);
+                }
+            }
+            // Floor division is spelled `~/` because `//` starts a comment.
+            '~' => {
+                if self.advance_if_match('/') {
+                    self.add_token(TokenType::TILDE_SLASH, None);
+                } else {
+                    self.add_token(TokenType::TILDE, None);
                 }
             }
             ' ' => (),
             '\r' => (),
             '\t' => (),
-            '\n' => self.line += 1,
+            '\n' => (),
             c => {
-                if c.is_digit(10) {
+                if c.is_ascii_digit() {
                     self.number();
                 } else if Self::is_my_alphabetic(c) {
                     self.identifier();
                 } else {
-                    self.error(self.line, "Unexpected character".to_string());
+                    self.error("Unexpected character.".to_string());
                 }
             }
         }
     }
 
+    /// Skips a `/* ... */` comment whose opening `/*` was just consumed.
+    /// Comments nest, so every `/*` inside needs its own `*/`.
+    fn block_comment(&mut self) {
+        let mut depth = 1;
+        while depth > 0 {
+            if self.is_at_end() {
+                let span = Span::new(self.start, self.start + 2);
+                let message = "Unterminated block comment.".to_string();
+                self.error_at(self.start_line, self.start_column, span, message);
+                self.add_token(TokenType::ERROR, None);
+                return;
+            }
+
+            if self.peek() == '/' && self.peek_next() == '*' {
+                depth += 1;
+                self.advance();
+            } else if self.peek() == '*' && self.peek_next() == '/' {
+                depth -= 1;
+                self.advance();
+            }
+            self.advance();
+        }
+    }
+
+    /// Consumes the next character, keeping `line` and `column` in sync.
     fn advance(&mut self) -> char {
         let c = self.char_at(self.current);
-        self.current += 1;
-        std::char::from_u32(c as u32).unwrap()
+        self.current += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        c
     }
 
-    fn addToken(&mut self, token_type: TokenType, literal: Option<Literal>) {
+    fn add_token(&mut self, token_type: TokenType, literal: Option<Literal>) {
         let text: String = self.substring(self.start, self.current);
-        self.tokens
-            .push(Token::new(text, token_type, self.line, literal));
+        self.tokens.push(Token::new(
+            text,
+            token_type,
+            self.start_line,
+            self.start_column,
+            self.start,
+            literal,
+        ));
     }
 
-    fn error(&mut self, line: usize, message: String) {
-        self.report(line, "".to_string(), message);
+    /// Reports an error covering the lexeme scanned so far, and leaves an
+    /// ERROR token in its place so the parser doesn't report the gap again.
+    fn error(&mut self, message: String) {
+        let span = Span::new(self.start, self.current);
+        self.error_at(self.start_line, self.start_column, span, message);
+        self.add_token(TokenType::ERROR, None);
     }
 
-    fn report(&mut self, line: usize, where_happened: String, message: String) {
-        eprintln!("[{line}] Error {where_happened}: {message}");
-        self.error = Some(Error::new(line, message));
+    fn error_at(&mut self, line: usize, column: usize, span: Span, message: String) {
+        self.errors.push(Error::new(line, column, span, message));
     }
 
     fn advance_if_match(&mut self, expected: char) -> bool {
@@ -174,7 +303,7 @@ impl Scanner {
         if self.char_at(self.current) != expected {
             return false;
         }
-        self.current += 1;
+        self.advance();
         true
     }
 
@@ -187,45 +316,244 @@ impl Scanner {
     }
 
     fn peek_next(&self) -> char {
-        if self.current + 1 >= self.source.len() {
+        if self.is_at_end() {
             return '\0';
         } else {
-            return self.char_at(self.current + 1);
+            return self.char_at(self.current + self.peek().len_utf8());
         }
     }
 
+    /// Scans a string literal, or the rest of one after an interpolated
+    /// expression. `"a ${b} c"` becomes INTERPOLATION("a "), the tokens of
+    /// `b`, and STRING(" c"); `interpolations` counts the braces opened
+    /// inside each pending `${` so the matching `}` is found.
     fn string(&mut self) {
+        let mut value = String::new();
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1;
+            if self.peek() == '$' && self.peek_next() == '{' {
+                self.advance();
+                self.advance();
+                self.interpolations.push(0);
+                self.add_token(TokenType::INTERPOLATION, Some(Literal::String(value)));
+                return;
+            }
+
+            let c = self.advance();
+            if c == '\\' {
+                if let Some(escaped) = self.escape() {
+                    value.push(escaped);
+                }
+            } else {
+                value.push(c);
             }
-            self.advance();
         }
 
         if self.is_at_end() {
-            self.error(self.line, "Unterminated string.".to_string());
+            self.error("Unterminated string.".to_string());
             return;
         }
         self.advance();
-        let value: String = self.substring(self.start + 1, self.current - 1);
-        self.addToken(TokenType::STRING, Some(Literal::String(value)));
+        self.add_token(TokenType::STRING, Some(Literal::String(value)));
+    }
+
+    /// Decodes the escape sequence following a backslash that has just been
+    /// consumed. Reports an error and returns `None` if it is malformed.
+    fn escape(&mut self) -> Option<char> {
+        let line = self.line;
+        let column = self.column - 1;
+        let start = self.current - 1;
+
+        if self.is_at_end() {
+            return None;
+        }
+
+        let c = self.advance();
+        let escaped = match c {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            '$' => Some('$'),
+            'u' => return self.unicode_escape(line, column, start),
+            _ => None,
+        };
+
+        if escaped.is_none() {
+            let message = format!("Unknown escape sequence '\\{}'.", c);
+            self.error_at(line, column, Span::new(start, self.current), message);
+        }
+        escaped
+    }
+
+    /// Decodes the `{XXXX}` part of a `\u{XXXX}` escape: one to six hex
+    /// digits naming a Unicode scalar value.
+    fn unicode_escape(&mut self, line: usize, column: usize, start: usize) -> Option<char> {
+        if !self.advance_if_match('{') {
+            let span = Span::new(start, self.current);
+            self.error_at(line, column, span, "Expect '{' after '\\u'.".to_string());
+            return None;
+        }
+
+        let digits_start = self.current;
+        while self.peek().is_ascii_hexdigit() {
+            self.advance();
+        }
+        let digits = self.substring(digits_start, self.current);
+
+        if !self.advance_if_match('}') || digits.is_empty() || digits.len() > 6 {
+            let span = Span::new(start, self.current);
+            let message = "Unicode escape must be '\\u{' followed by 1 to 6 hex digits and '}'.";
+            self.error_at(line, column, span, message.to_string());
+            return None;
+        }
+
+        let code_point = u32::from_str_radix(&digits, 16).ok()?;
+        let escaped = char::from_u32(code_point);
+        if escaped.is_none() {
+            let span = Span::new(start, self.current);
+            let message = format!("Invalid Unicode code point U+{:X}.", code_point);
+            self.error_at(line, column, span, message);
+        }
+        escaped
     }
 
+    /// Scans a number literal. Besides plain decimals like `12.5` this
+    /// accepts `0x`, `0b` and `0o` prefixes, `_` separators between digits
+    /// and exponents like `6.02e23` or `1e-9`. Literals without a fraction
+    /// or exponent are integers.
     fn number(&mut self) {
-        while self.peek().is_digit(10) {
+        if self.char_at(self.start) == '0' {
+            let radix = match self.peek() {
+                'x' => Some((16, "hexadecimal")),
+                'b' => Some((2, "binary")),
+                'o' => Some((8, "octal")),
+                _ => None,
+            };
+            if let Some((radix, name)) = radix {
+                self.advance();
+                self.radix_number(radix, name);
+                return;
+            }
+        }
+
+        self.digits(10);
+        let mut is_float = false;
+
+        if self.peek() == '.' && self.peek_next().is_ascii_digit() {
+            is_float = true;
             self.advance();
+            self.digits(10);
         }
 
-        if self.peek() == '.' && self.peek_next().is_digit(10) {
+        if self.peek() == 'e' || self.peek() == 'E' {
+            is_float = true;
             self.advance();
-            while self.peek().is_digit(10) {
+            if self.peek() == '+' || self.peek() == '-' {
                 self.advance();
             }
+            if self.digits(10) == 0 {
+                self.error("Expect digits in exponent.".to_string());
+                return;
+            }
+        }
+
+        if !self.check_number_end("decimal") {
+            return;
+        }
+
+        let text = self.substring(self.start, self.current).replace('_', "");
+        if is_float {
+            // Rust parses literals past `f64::MAX` as infinity.
+            match text.parse::<f64>() {
+                Ok(number) if number.is_finite() => {
+                    self.add_token(TokenType::NUMBER, Some(Literal::Float(number)))
+                }
+                _ => self.error("Number literal is too large.".to_string()),
+            }
+        } else {
+            match text.parse::<i64>() {
+                Ok(number) => self.add_token(TokenType::NUMBER, Some(Literal::Integer(number))),
+                Err(_) => {
+                    let number = BigInt::from_digits(&text, 10);
+                    self.add_token(TokenType::NUMBER, Some(Literal::BigInt(number)));
+                }
+            }
         }
+    }
 
-        let number: f32 = self.substring(self.start, self.current).parse().unwrap();
+    fn radix_number(&mut self, radix: u32, name: &str) {
+        let digits_start = self.current;
+        if self.digits(radix) == 0 {
+            // A separator can't come straight after the prefix, as in `0x_FF`.
+            if self.peek() == '_' {
+                self.check_number_end(name);
+                return;
+            }
+            let prefix = self.substring(self.start, self.current);
+            self.error(format!("Expect {} digits after '{}'.", name, prefix));
+            return;
+        }
 
-        self.addToken(TokenType::NUMBER, Some(Literal::Float(number)));
+        if !self.check_number_end(name) {
+            return;
+        }
+
+        let digits = self.substring(digits_start, self.current);
+        let number = digits
+            .chars()
+            .filter_map(|c| c.to_digit(radix))
+            .try_fold(0i64, |number, digit| {
+                number.checked_mul(radix as i64)?.checked_add(digit as i64)
+            });
+        match number {
+            Some(number) => self.add_token(TokenType::NUMBER, Some(Literal::Integer(number))),
+            None => {
+                let number = BigInt::from_digits(&digits, radix);
+                self.add_token(TokenType::NUMBER, Some(Literal::BigInt(number)));
+            }
+        }
+    }
+
+    /// Consumes digits of the given radix, allowing a single `_` between
+    /// two of them, and returns how many digits were consumed.
+    fn digits(&mut self, radix: u32) -> usize {
+        let mut count = 0;
+        loop {
+            // Only ASCII precedes the digits of a number, so the previous
+            // byte is the previous character.
+            let previous = self.source.as_bytes()[self.current - 1] as char;
+            if self.peek().is_digit(radix) {
+                count += 1;
+            } else if !(self.peek() == '_'
+                && previous.is_digit(radix)
+                && self.peek_next().is_digit(radix))
+            {
+                return count;
+            }
+            self.advance();
+        }
+    }
+
+    /// Reports stray characters glued to the end of a number literal, such
+    /// as the `2` in `0b102` or a trailing `_`.
+    fn check_number_end(&mut self, name: &str) -> bool {
+        let c = self.peek();
+        if c == '_' || c.is_ascii_digit() || (name != "decimal" && c.is_ascii_alphabetic()) {
+            while Self::is_my_alphanumeric(self.peek()) {
+                self.advance();
+            }
+            let message = if c == '_' {
+                "Digit separator '_' must be between two digits.".to_string()
+            } else {
+                format!("Invalid digit '{}' in {} literal.", c, name)
+            };
+            self.error(message);
+            return false;
+        }
+
+        true
     }
 
     fn identifier(&mut self) {
@@ -239,9 +567,9 @@ impl Scanner {
                 .iter()
                 .find_map(|(key, val)| if *key == text { Some(*val) } else { None })
         {
-            self.addToken(token_type, None)
+            self.add_token(token_type, None)
         } else {
-            self.addToken(TokenType::IDENTIFIER, None);
+            self.add_token(TokenType::IDENTIFIER, None);
         }
     }
 
@@ -249,10 +577,293 @@ impl Scanner {
         self.keywords.clone()
     }
 
+    /// Identifiers follow Unicode's UAX #31: they start with an XID_Start
+    /// character or `_` and continue with XID_Continue characters.
     fn is_my_alphabetic(c: char) -> bool {
-        (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || (c == '_')
+        unicode_ident::is_xid_start(c) || c == '_'
     }
     fn is_my_alphanumeric(c: char) -> bool {
-        c.is_digit(10) || Self::is_my_alphabetic(c)
+        unicode_ident::is_xid_continue(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(source: &str) -> Vec<Token> {
+        let mut scanner = Scanner::new(source.to_string());
+        let tokens = scanner.scan_tokens();
+        assert!(scanner.errors().is_empty(), "{:?}", scanner.errors());
+        tokens
+    }
+
+    fn errors(source: &str) -> Vec<(usize, usize, String)> {
+        let mut scanner = Scanner::new(source.to_string());
+        scanner.scan_tokens();
+        Error::summarize(scanner.errors())
+    }
+
+    fn string_literal(source: &str) -> String {
+        match scan(source)[0].literal() {
+            Some(Literal::String(string)) => string,
+            literal => panic!("expected a string literal, got {:?}", literal),
+        }
+    }
+
+    fn token_types(source: &str) -> Vec<TokenType> {
+        scan(source)
+            .iter()
+            .map(|token| token.token_type())
+            .collect()
+    }
+
+    #[test]
+    fn decodes_escape_sequences() {
+        let cases = [
+            (r#""\n""#, "\n"),
+            (r#""\t""#, "\t"),
+            (r#""\r""#, "\r"),
+            (r#""\0""#, "\0"),
+            (r#""\\""#, "\\"),
+            (r#""\"""#, "\""),
+            (r#""\$""#, "$"),
+            (r#""\u{41}""#, "A"),
+            (r#""\u{e9}""#, "é"),
+            (r#""\u{1F600}""#, "😀"),
+            (r#""a\tb\u{1F600}c""#, "a\tb😀c"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(string_literal(source), expected, "{}", source);
+        }
+    }
+
+    #[test]
+    fn reports_malformed_escapes_at_the_backslash() {
+        assert_eq!(
+            errors("\"ab\\q\""),
+            [(1, 4, "Unknown escape sequence '\\q'.".to_string())]
+        );
+        assert_eq!(
+            errors("\n  \"\\u{}\""),
+            [(
+                2,
+                4,
+                "Unicode escape must be '\\u{' followed by 1 to 6 hex digits and '}'.".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("\"\\u{1234567}\""),
+            [(
+                1,
+                2,
+                "Unicode escape must be '\\u{' followed by 1 to 6 hex digits and '}'.".to_string()
+            )]
+        );
+        assert_eq!(
+            errors("\"\\u{110000}\""),
+            [(1, 2, "Invalid Unicode code point U+110000.".to_string())]
+        );
+        assert_eq!(
+            errors("\"x \\u41\""),
+            [(1, 4, "Expect '{' after '\\u'.".to_string())]
+        );
+    }
+
+    fn number_literal(source: &str) -> Literal {
+        scan(source)[0]
+            .literal()
+            .expect("number should have a literal")
+    }
+
+    #[test]
+    fn scans_number_literals() {
+        let cases = [
+            ("0", "0"),
+            ("1_000_000", "1000000"),
+            ("0xFF", "255"),
+            ("0xdead_BEEF", "3735928559"),
+            ("0b1010", "10"),
+            ("0b1111_0000", "240"),
+            ("0o17", "15"),
+            ("0x1_0000_0000_0000_0000", "18446744073709551616"),
+            ("99999999999999999999", "99999999999999999999"),
+            ("12.5", "12.5"),
+            ("1_000.000_5", "1000.0005"),
+            ("6.02e23", "6.02e23"),
+            ("1E3", "1000.0"),
+            ("1e+3", "1000.0"),
+            ("25e-1", "2.5"),
+            ("1e1_0", "10000000000.0"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(number_literal(source).to_string(), expected, "{}", source);
+        }
+        assert!(matches!(
+            number_literal("0x7fff_ffff_ffff_ffff"),
+            Literal::Integer(i64::MAX)
+        ));
+        assert!(matches!(
+            number_literal("0x8000_0000_0000_0000"),
+            Literal::BigInt(_)
+        ));
+        assert!(matches!(number_literal("1e2"), Literal::Float(_)));
+    }
+
+    #[test]
+    fn reports_malformed_number_literals() {
+        let separator = "Digit separator '_' must be between two digits.".to_string();
+        let cases = [
+            ("0x", "Expect hexadecimal digits after '0x'.".to_string()),
+            ("0b", "Expect binary digits after '0b'.".to_string()),
+            ("0o9", "Expect octal digits after '0o'.".to_string()),
+            ("1e", "Expect digits in exponent.".to_string()),
+            ("1e+", "Expect digits in exponent.".to_string()),
+            ("0b102", "Invalid digit '2' in binary literal.".to_string()),
+            ("0o78", "Invalid digit '8' in octal literal.".to_string()),
+            (
+                "0xFG",
+                "Invalid digit 'G' in hexadecimal literal.".to_string(),
+            ),
+            ("1_", separator.clone()),
+            ("1__0", separator.clone()),
+            ("0x_FF", separator.clone()),
+            ("0b1_", separator.clone()),
+            ("1e400", "Number literal is too large.".to_string()),
+        ];
+        for (source, message) in cases {
+            let source = format!("print {};", source);
+            assert_eq!(errors(&source), [(1, 7, message)], "{}", source);
+        }
+    }
+
+    #[test]
+    fn skips_nested_block_comments() {
+        let tokens = scan("a /* outer /* inner */ still comment */ b");
+        let lexemes: Vec<String> = tokens.iter().map(|token| token.lexeme()).collect();
+        assert_eq!(lexemes, ["a", "b", ""]);
+    }
+
+    #[test]
+    fn counts_lines_inside_block_comments() {
+        let tokens = scan("/* one\ntwo /* three\n*/ four\n*/  x");
+        assert_eq!(tokens[0].lexeme(), "x");
+        assert_eq!((tokens[0].line(), tokens[0].column()), (4, 5));
+    }
+
+    #[test]
+    fn reports_unterminated_block_comment_at_its_opening() {
+        assert_eq!(
+            errors("print 1;\n  /* a /* b */\n\n"),
+            [(2, 3, "Unterminated block comment.".to_string())]
+        );
+    }
+
+    #[test]
+    fn splits_interpolated_strings_into_segments() {
+        let tokens = scan(r#""a ${b} c ${d}""#);
+        let segments: Vec<(TokenType, Option<String>)> = tokens
+            .iter()
+            .map(|token| (token.token_type(), token.literal().map(|l| l.to_string())))
+            .collect();
+        let string = |value: &str| Some(value.to_string());
+        assert_eq!(
+            segments,
+            [
+                (TokenType::INTERPOLATION, string("a ")),
+                (TokenType::IDENTIFIER, None),
+                (TokenType::INTERPOLATION, string(" c ")),
+                (TokenType::IDENTIFIER, None),
+                (TokenType::STRING, string("")),
+                (TokenType::EOF, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn tilde_slash_is_floor_division_and_double_slash_a_comment() {
+        assert_eq!(
+            token_types("a ~/ b // c\n~d"),
+            [
+                TokenType::IDENTIFIER,
+                TokenType::TILDE_SLASH,
+                TokenType::IDENTIFIER,
+                TokenType::TILDE,
+                TokenType::IDENTIFIER,
+                TokenType::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn braces_inside_interpolations_do_not_end_them() {
+        assert_eq!(
+            token_types(r#""${ {} }x""#),
+            [
+                TokenType::INTERPOLATION,
+                TokenType::LEFT_BRACE,
+                TokenType::RIGHT_BRACE,
+                TokenType::STRING,
+                TokenType::EOF,
+            ]
+        );
+    }
+
+    #[test]
+    fn strings_nest_inside_interpolations() {
+        let tokens = scan(r#""a ${ "b ${c}" } d""#);
+        let lexemes: Vec<String> = tokens.iter().map(|token| token.lexeme()).collect();
+        assert_eq!(
+            lexemes,
+            [r#""a ${"#, r#""b ${"#, "c", r#"}""#, r#"} d""#, ""]
+        );
+    }
+
+    #[test]
+    fn keeps_multi_byte_characters_in_strings() {
+        assert_eq!(string_literal("\"héllo 😀 wörld\""), "héllo 😀 wörld");
+    }
+
+    #[test]
+    fn scans_unicode_identifiers() {
+        let tokens = scan("var café = π + _ñ1;");
+        let identifiers: Vec<String> = tokens
+            .iter()
+            .filter(|token| token.token_type() == TokenType::IDENTIFIER)
+            .map(|token| token.lexeme())
+            .collect();
+        assert_eq!(identifiers, ["café", "π", "_ñ1"]);
+        assert_eq!(
+            errors("var 😀 = 1;"),
+            [(1, 5, "Unexpected character.".to_string())]
+        );
+    }
+
+    #[test]
+    fn counts_columns_in_characters_and_offsets_in_bytes() {
+        let tokens = scan("\"😀é\" + naïve\n  x");
+        let positions: Vec<(String, usize, usize, usize, usize)> = tokens
+            .iter()
+            .map(|token| {
+                let span = token.span();
+                (
+                    token.lexeme(),
+                    token.line(),
+                    token.column(),
+                    span.start(),
+                    span.end(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            positions,
+            [
+                ("\"😀é\"".to_string(), 1, 1, 0, 8),
+                ("+".to_string(), 1, 6, 9, 10),
+                ("naïve".to_string(), 1, 8, 11, 17),
+                ("x".to_string(), 2, 3, 20, 21),
+                ("".to_string(), 2, 4, 21, 21),
+            ]
+        );
     }
 }
@@ -0,0 +1,392 @@
+use std::rc::Rc;
+
+use crate::{
+    expression::{Expression, Variable},
+    span::Span,
+    token::Token,
+    visitor::{StmtMutVisitor, StmtVisitor},
+};
+
+#[derive(Debug)]
+pub struct ExpressionStmt {
+    expression: Expression,
+    span: Span,
+}
+
+impl ExpressionStmt {
+    pub fn new(expression: Expression, span: Span) -> Self {
+        ExpressionStmt {
+            expression: expression,
+            span: span,
+        }
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    pub fn expression_mut(&mut self) -> &mut Expression {
+        &mut self.expression
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub struct Print {
+    expression: Expression,
+    span: Span,
+}
+
+impl Print {
+    pub fn new(expression: Expression, span: Span) -> Self {
+        Print {
+            expression: expression,
+            span: span,
+        }
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    pub fn expression_mut(&mut self) -> &mut Expression {
+        &mut self.expression
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub struct Var {
+    name: Token,
+    initializer: Option<Expression>,
+    span: Span,
+}
+
+impl Var {
+    pub fn new(name: Token, initializer: Option<Expression>, span: Span) -> Self {
+        Var {
+            name: name,
+            initializer: initializer,
+            span: span,
+        }
+    }
+
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    pub fn initializer(&self) -> Option<&Expression> {
+        self.initializer.as_ref()
+    }
+
+    pub fn initializer_mut(&mut self) -> Option<&mut Expression> {
+        self.initializer.as_mut()
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub struct Block {
+    statements: Vec<Stmt>,
+    span: Span,
+}
+
+impl Block {
+    pub fn new(statements: Vec<Stmt>, span: Span) -> Self {
+        Block {
+            statements: statements,
+            span: span,
+        }
+    }
+
+    pub fn statements(&self) -> &[Stmt] {
+        &self.statements
+    }
+
+    pub fn statements_mut(&mut self) -> &mut [Stmt] {
+        &mut self.statements
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub struct If {
+    condition: Expression,
+    then_branch: Box<Stmt>,
+    else_branch: Option<Box<Stmt>>,
+    span: Span,
+}
+
+impl If {
+    pub fn new(
+        condition: Expression,
+        then_branch: Stmt,
+        else_branch: Option<Stmt>,
+        span: Span,
+    ) -> Self {
+        If {
+            condition: condition,
+            then_branch: Box::new(then_branch),
+            else_branch: else_branch.map(Box::new),
+            span: span,
+        }
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn then_branch(&self) -> &Stmt {
+        &self.then_branch
+    }
+
+    pub fn else_branch(&self) -> Option<&Stmt> {
+        self.else_branch.as_deref()
+    }
+
+    pub fn condition_mut(&mut self) -> &mut Expression {
+        &mut self.condition
+    }
+
+    pub fn then_branch_mut(&mut self) -> &mut Stmt {
+        &mut self.then_branch
+    }
+
+    pub fn else_branch_mut(&mut self) -> Option<&mut Stmt> {
+        self.else_branch.as_deref_mut()
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub struct While {
+    condition: Expression,
+    body: Box<Stmt>,
+    span: Span,
+}
+
+impl While {
+    pub fn new(condition: Expression, body: Stmt, span: Span) -> Self {
+        While {
+            condition: condition,
+            body: Box::new(body),
+            span: span,
+        }
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn body(&self) -> &Stmt {
+        &self.body
+    }
+
+    pub fn condition_mut(&mut self) -> &mut Expression {
+        &mut self.condition
+    }
+
+    pub fn body_mut(&mut self) -> &mut Stmt {
+        &mut self.body
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub struct Function {
+    name: Token,
+    params: Vec<Token>,
+    body: Vec<Stmt>,
+    span: Span,
+}
+
+impl Function {
+    pub fn new(name: Token, params: Vec<Token>, body: Vec<Stmt>, span: Span) -> Self {
+        Function {
+            name: name,
+            params: params,
+            body: body,
+            span: span,
+        }
+    }
+
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    pub fn params(&self) -> &[Token] {
+        &self.params
+    }
+
+    pub fn body(&self) -> &[Stmt] {
+        &self.body
+    }
+
+    pub fn body_mut(&mut self) -> &mut [Stmt] {
+        &mut self.body
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub struct Return {
+    keyword: Token,
+    value: Option<Expression>,
+    span: Span,
+}
+
+impl Return {
+    pub fn new(keyword: Token, value: Option<Expression>, span: Span) -> Self {
+        Return {
+            keyword: keyword,
+            value: value,
+            span: span,
+        }
+    }
+
+    pub fn keyword(&self) -> &Token {
+        &self.keyword
+    }
+
+    pub fn value(&self) -> Option<&Expression> {
+        self.value.as_ref()
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut Expression> {
+        self.value.as_mut()
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub struct Class {
+    name: Token,
+    superclass: Option<Variable>,
+    methods: Vec<Rc<Function>>,
+    span: Span,
+}
+
+impl Class {
+    pub fn new(
+        name: Token,
+        superclass: Option<Variable>,
+        methods: Vec<Rc<Function>>,
+        span: Span,
+    ) -> Self {
+        Class {
+            name: name,
+            superclass: superclass,
+            methods: methods,
+            span: span,
+        }
+    }
+
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    pub fn superclass(&self) -> Option<&Variable> {
+        self.superclass.as_ref()
+    }
+
+    pub fn methods(&self) -> &[Rc<Function>] {
+        &self.methods
+    }
+
+    /// Panics once the class has run, since its methods are then shared.
+    pub fn methods_mut(&mut self) -> impl Iterator<Item = &mut Function> {
+        self.methods
+            .iter_mut()
+            .map(|method| Rc::get_mut(method).expect("methods are only rewritten before they run"))
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+#[derive(Debug)]
+pub enum Stmt {
+    Expression(ExpressionStmt),
+    Print(Print),
+    Var(Var),
+    Block(Block),
+    If(If),
+    While(While),
+    Function(Rc<Function>),
+    Return(Return),
+    Class(Class),
+}
+
+impl Stmt {
+    /// Calls the `visitor` method matching this statement's kind.
+    pub fn accept<R, V: StmtVisitor<R> + ?Sized>(&self, visitor: &mut V) -> R {
+        match self {
+            Stmt::Expression(statement) => visitor.visit_expression_stmt(statement),
+            Stmt::Print(statement) => visitor.visit_print(statement),
+            Stmt::Var(statement) => visitor.visit_var(statement),
+            Stmt::Block(statement) => visitor.visit_block(statement),
+            Stmt::If(statement) => visitor.visit_if(statement),
+            Stmt::While(statement) => visitor.visit_while(statement),
+            Stmt::Function(statement) => visitor.visit_function(statement),
+            Stmt::Return(statement) => visitor.visit_return(statement),
+            Stmt::Class(statement) => visitor.visit_class(statement),
+        }
+    }
+
+    /// Like `accept`, but lets the visitor modify the statement. Function
+    /// declarations are shared once they run, so this panics on one that has.
+    pub fn accept_mut<R, V: StmtMutVisitor<R> + ?Sized>(&mut self, visitor: &mut V) -> R {
+        match self {
+            Stmt::Expression(statement) => visitor.visit_expression_stmt(statement),
+            Stmt::Print(statement) => visitor.visit_print(statement),
+            Stmt::Var(statement) => visitor.visit_var(statement),
+            Stmt::Block(statement) => visitor.visit_block(statement),
+            Stmt::If(statement) => visitor.visit_if(statement),
+            Stmt::While(statement) => visitor.visit_while(statement),
+            Stmt::Function(statement) => visitor.visit_function(
+                Rc::get_mut(statement).expect("functions are only rewritten before they run"),
+            ),
+            Stmt::Return(statement) => visitor.visit_return(statement),
+            Stmt::Class(statement) => visitor.visit_class(statement),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Expression(statement) => statement.span(),
+            Stmt::Print(statement) => statement.span(),
+            Stmt::Var(statement) => statement.span(),
+            Stmt::Block(statement) => statement.span(),
+            Stmt::If(statement) => statement.span(),
+            Stmt::While(statement) => statement.span(),
+            Stmt::Function(statement) => statement.span(),
+            Stmt::Return(statement) => statement.span(),
+            Stmt::Class(statement) => statement.span(),
+        }
+    }
+}
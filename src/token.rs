@@ -1,25 +1,31 @@
 use std::fmt::Display;
 
-use crate::token_type::TokenType;
+use crate::{bigint::BigInt, span::Span, token_type::TokenType};
 
 #[derive(Clone, Debug)]
 pub struct Token {
     token_type: TokenType,
     lexeme: String,
     line: usize,
+    column: usize,
+    offset: usize,
     literal: Option<Literal>,
 }
 
 #[derive(Clone, Debug)]
 pub enum Literal {
     String(String),
-    Float(f32),
+    Integer(i64),
+    BigInt(BigInt),
+    Float(f64),
 }
 
 impl Display for Literal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Literal::Float(float) => write!(f, "{}", float),
+            Literal::Integer(integer) => write!(f, "{}", integer),
+            Literal::BigInt(integer) => write!(f, "{}", integer),
+            Literal::Float(float) => write!(f, "{:?}", float),
             Literal::String(string) => write!(f, "{}", string),
         }
     }
@@ -30,6 +36,8 @@ impl Token {
         lexeme: String,
         token_type: TokenType,
         line: usize,
+        column: usize,
+        offset: usize,
         literal_opt: Option<Literal>,
     ) -> Self {
         if let Some(literal) = literal_opt {
@@ -37,6 +45,8 @@ impl Token {
                 token_type: token_type,
                 lexeme: lexeme,
                 line: line,
+                column: column,
+                offset: offset,
                 literal: Some(literal),
             };
         }
@@ -45,6 +55,8 @@ impl Token {
             token_type: token_type,
             lexeme: lexeme,
             line: line,
+            column: column,
+            offset: offset,
             literal: None,
         }
     }
@@ -53,12 +65,41 @@ impl Token {
         self.line
     }
 
+    /// 1-based column of the first character of the lexeme.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Byte offset of the lexeme in the source.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn span(&self) -> Span {
+        Span::new(self.offset, self.offset + self.lexeme.len())
+    }
+
+    /// Line and column just past the last character of the lexeme.
+    pub fn end(&self) -> (usize, usize) {
+        match self.lexeme.rfind('\n') {
+            Some(i) => (
+                self.line + self.lexeme.matches('\n').count(),
+                self.lexeme[i + 1..].chars().count() + 1,
+            ),
+            None => (self.line, self.column + self.lexeme.chars().count()),
+        }
+    }
+
     pub fn lexeme(&self) -> String {
         self.lexeme.clone()
     }
 
     pub fn token_type(&self) -> TokenType {
-        self.token_type.clone()
+        self.token_type
+    }
+
+    pub fn literal(&self) -> Option<Literal> {
+        self.literal.clone()
     }
 }
 
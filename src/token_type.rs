@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum TokenType {
     // Single-character tokens.
@@ -14,6 +15,12 @@ pub enum TokenType {
     SEMICOLON,
     SLASH,
     STAR,
+    PERCENT,
+    AMPERSAND,
+    PIPE,
+    CARET,
+    QUESTION,
+    COLON,
 
     // One or two character tokens.
     BANG,
@@ -24,10 +31,24 @@ pub enum TokenType {
     GREATER_EQUAL,
     LESS,
     LESS_EQUAL,
+    LESS_LESS,
+    GREATER_GREATER,
+    STAR_STAR,
+    TILDE,
+    /// Floor division, `~/`, since `//` starts a comment.
+    TILDE_SLASH,
+    PLUS_EQUAL,
+    PLUS_PLUS,
+    MINUS_EQUAL,
+    MINUS_MINUS,
+    STAR_EQUAL,
+    SLASH_EQUAL,
+    PERCENT_EQUAL,
 
     // Literals.
     IDENTIFIER,
     STRING,
+    INTERPOLATION,
     NUMBER,
 
     // Keywords.
@@ -48,6 +69,8 @@ pub enum TokenType {
     VAR,
     WHILE,
 
+    /// A lexeme the scanner couldn't make sense of and already reported.
+    ERROR,
     EOF,
 }
 
@@ -64,6 +87,24 @@ impl Display for TokenType {
             TokenType::PLUS => write!(f, "PLUS"),
             TokenType::SEMICOLON => write!(f, "SEMICOLON"),
             TokenType::SLASH => write!(f, "SLASH"),
+            TokenType::TILDE_SLASH => write!(f, "TILDE_SLASH"),
+            TokenType::PERCENT => write!(f, "PERCENT"),
+            TokenType::AMPERSAND => write!(f, "AMPERSAND"),
+            TokenType::PIPE => write!(f, "PIPE"),
+            TokenType::CARET => write!(f, "CARET"),
+            TokenType::LESS_LESS => write!(f, "LESS_LESS"),
+            TokenType::GREATER_GREATER => write!(f, "GREATER_GREATER"),
+            TokenType::STAR_STAR => write!(f, "STAR_STAR"),
+            TokenType::TILDE => write!(f, "TILDE"),
+            TokenType::QUESTION => write!(f, "QUESTION"),
+            TokenType::COLON => write!(f, "COLON"),
+            TokenType::PLUS_EQUAL => write!(f, "PLUS_EQUAL"),
+            TokenType::PLUS_PLUS => write!(f, "PLUS_PLUS"),
+            TokenType::MINUS_EQUAL => write!(f, "MINUS_EQUAL"),
+            TokenType::MINUS_MINUS => write!(f, "MINUS_MINUS"),
+            TokenType::STAR_EQUAL => write!(f, "STAR_EQUAL"),
+            TokenType::SLASH_EQUAL => write!(f, "SLASH_EQUAL"),
+            TokenType::PERCENT_EQUAL => write!(f, "PERCENT_EQUAL"),
             TokenType::STAR => write!(f, "STAR"),
             TokenType::BANG => write!(f, "BANG"),
             TokenType::BANG_EQUAL => write!(f, "BANG_EQUAL"),
@@ -75,6 +116,7 @@ impl Display for TokenType {
             TokenType::LESS_EQUAL => write!(f, "LESS_EQUAL"),
             TokenType::IDENTIFIER => write!(f, "IDENTIFIER"),
             TokenType::STRING => write!(f, "STRING"),
+            TokenType::INTERPOLATION => write!(f, "INTERPOLATION"),
             TokenType::NUMBER => write!(f, "NUMBER"),
             TokenType::AND => write!(f, "AND"),
             TokenType::CLASS => write!(f, "CLASS"),
@@ -92,6 +134,7 @@ impl Display for TokenType {
             TokenType::TRUE => write!(f, "TRUE"),
             TokenType::VAR => write!(f, "VAR"),
             TokenType::WHILE => write!(f, "WHILE"),
+            TokenType::ERROR => write!(f, "ERROR"),
             TokenType::EOF => write!(f, "EOF"),
         }
     }
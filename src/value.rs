@@ -0,0 +1,119 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use crate::{
+    bigint::BigInt,
+    callable::Callable,
+    class::{LoxClass, LoxInstance},
+    function::{LoxFunction, NativeFunction},
+};
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Integer(i64),
+    BigInt(BigInt),
+    Float(f64),
+    String(String),
+    Function(Rc<LoxFunction>),
+    NativeFunction(Rc<NativeFunction>),
+    Class(Rc<LoxClass>),
+    Instance(Rc<RefCell<LoxInstance>>),
+}
+
+impl Value {
+    /// Lox follows Ruby's rule: `false` and `nil` are falsey, everything else is truthy.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Nil => false,
+            Value::Bool(boolean) => *boolean,
+            _ => true,
+        }
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::Integer(_) | Value::BigInt(_) | Value::Float(_))
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Integer(integer) => Some(*integer as f64),
+            Value::BigInt(integer) => Some(integer.to_f64()),
+            Value::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+
+    pub fn as_big_int(&self) -> Option<BigInt> {
+        match self {
+            Value::Integer(integer) => Some(BigInt::from(*integer)),
+            Value::BigInt(integer) => Some(integer.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn as_callable(&self) -> Option<&dyn Callable> {
+        match self {
+            Value::Function(function) => Some(function.as_ref()),
+            Value::NativeFunction(function) => Some(function.as_ref()),
+            Value::Class(class) => Some(class),
+            _ => None,
+        }
+    }
+}
+
+/// Big integers shrink back to `Integer` whenever they fit in an `i64`, so
+/// each integer has a single representation.
+impl From<BigInt> for Value {
+    fn from(integer: BigInt) -> Self {
+        match integer.to_i64() {
+            Some(integer) => Value::Integer(integer),
+            None => Value::BigInt(integer),
+        }
+    }
+}
+
+/// Primitives compare by value, functions, classes and instances by identity.
+/// An integer equals a float with the same numeric value.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b,
+            (Value::BigInt(a), Value::Float(b)) | (Value::Float(b), Value::BigInt(a)) => {
+                a.to_f64() == *b
+            }
+            (Value::Integer(a), Value::Float(b)) | (Value::Float(b), Value::Integer(a)) => {
+                *a as f64 == *b
+            }
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
+            (Value::NativeFunction(a), Value::NativeFunction(b)) => Rc::ptr_eq(a, b),
+            (Value::Class(a), Value::Class(b)) => Rc::ptr_eq(a, b),
+            (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Nil => write!(f, "nil"),
+            Value::Bool(boolean) => write!(f, "{}", boolean),
+            Value::Integer(integer) => write!(f, "{}", integer),
+            Value::BigInt(integer) => write!(f, "{}", integer),
+            // `f64`'s Display already drops the `.0` of integral values and
+            // prints the shortest digits that round-trip otherwise.
+            Value::Float(float) => write!(f, "{}", float),
+            Value::String(string) => write!(f, "{}", string),
+            Value::Function(function) => write!(f, "{}", function),
+            Value::NativeFunction(function) => write!(f, "{}", function),
+            Value::Class(class) => write!(f, "{}", class),
+            Value::Instance(instance) => write!(f, "{}", instance.borrow()),
+        }
+    }
+}
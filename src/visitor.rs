@@ -0,0 +1,264 @@
+use crate::{
+    expression::{
+        Assign, Binary, Call, Conditional, Get, Grouping, Interpolation, LiteralExp, Logical, Set,
+        Super, This, Unary, Update, Variable,
+    },
+    statement::{Block, Class, ExpressionStmt, Function, If, Print, Return, Var, While},
+};
+
+/// One method per kind of expression node, dispatched to by
+/// `Expression::accept`. Visitors recurse into children themselves, e.g. by
+/// calling `binary.left().accept(self)`.
+pub trait Visitor<R> {
+    fn visit_literal(&mut self, literal: &LiteralExp) -> R;
+    fn visit_unary(&mut self, unary: &Unary) -> R;
+    fn visit_binary(&mut self, binary: &Binary) -> R;
+    fn visit_grouping(&mut self, grouping: &Grouping) -> R;
+    fn visit_variable(&mut self, variable: &Variable) -> R;
+    fn visit_assign(&mut self, assign: &Assign) -> R;
+    fn visit_logical(&mut self, logical: &Logical) -> R;
+    fn visit_conditional(&mut self, conditional: &Conditional) -> R;
+    fn visit_call(&mut self, call: &Call) -> R;
+    fn visit_this(&mut self, this: &This) -> R;
+    fn visit_get(&mut self, get: &Get) -> R;
+    fn visit_set(&mut self, set: &Set) -> R;
+    fn visit_super(&mut self, expression: &Super) -> R;
+    fn visit_interpolation(&mut self, interpolation: &Interpolation) -> R;
+    fn visit_update(&mut self, update: &Update) -> R;
+}
+
+/// A `Visitor` that may rewrite the nodes it visits, dispatched to by
+/// `Expression::accept_mut`.
+pub trait MutVisitor<R> {
+    fn visit_literal(&mut self, literal: &mut LiteralExp) -> R;
+    fn visit_unary(&mut self, unary: &mut Unary) -> R;
+    fn visit_binary(&mut self, binary: &mut Binary) -> R;
+    fn visit_grouping(&mut self, grouping: &mut Grouping) -> R;
+    fn visit_variable(&mut self, variable: &mut Variable) -> R;
+    fn visit_assign(&mut self, assign: &mut Assign) -> R;
+    fn visit_logical(&mut self, logical: &mut Logical) -> R;
+    fn visit_conditional(&mut self, conditional: &mut Conditional) -> R;
+    fn visit_call(&mut self, call: &mut Call) -> R;
+    fn visit_this(&mut self, this: &mut This) -> R;
+    fn visit_get(&mut self, get: &mut Get) -> R;
+    fn visit_set(&mut self, set: &mut Set) -> R;
+    fn visit_super(&mut self, expression: &mut Super) -> R;
+    fn visit_interpolation(&mut self, interpolation: &mut Interpolation) -> R;
+    fn visit_update(&mut self, update: &mut Update) -> R;
+}
+
+/// One method per kind of statement, dispatched to by `Stmt::accept`. Like
+/// `Visitor`, implementors recurse into nested statements and expressions.
+pub trait StmtVisitor<R> {
+    fn visit_expression_stmt(&mut self, statement: &ExpressionStmt) -> R;
+    fn visit_print(&mut self, statement: &Print) -> R;
+    fn visit_var(&mut self, statement: &Var) -> R;
+    fn visit_block(&mut self, block: &Block) -> R;
+    fn visit_if(&mut self, statement: &If) -> R;
+    fn visit_while(&mut self, statement: &While) -> R;
+    fn visit_function(&mut self, function: &Function) -> R;
+    fn visit_return(&mut self, statement: &Return) -> R;
+    fn visit_class(&mut self, class: &Class) -> R;
+}
+
+/// A `StmtVisitor` that may rewrite the statements it visits, dispatched to
+/// by `Stmt::accept_mut`.
+pub trait StmtMutVisitor<R> {
+    fn visit_expression_stmt(&mut self, statement: &mut ExpressionStmt) -> R;
+    fn visit_print(&mut self, statement: &mut Print) -> R;
+    fn visit_var(&mut self, statement: &mut Var) -> R;
+    fn visit_block(&mut self, block: &mut Block) -> R;
+    fn visit_if(&mut self, statement: &mut If) -> R;
+    fn visit_while(&mut self, statement: &mut While) -> R;
+    fn visit_function(&mut self, function: &mut Function) -> R;
+    fn visit_return(&mut self, statement: &mut Return) -> R;
+    fn visit_class(&mut self, class: &mut Class) -> R;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ast_printer::AstPrinter,
+        expression::Expression,
+        parser::Parser,
+        scanner::Scanner,
+        statement::Stmt,
+        token::{Literal, Token},
+        token_type::TokenType,
+    };
+
+    /// Replaces every reference to the variable `x` with the literal `42`.
+    struct Inline;
+
+    impl Inline {
+        fn rewrite(&mut self, expression: &mut Expression) {
+            match expression {
+                Expression::Variable(variable) if variable.name().lexeme() == "x" => {
+                    let token = variable.name();
+                    let literal = Token::new(
+                        "42".to_string(),
+                        TokenType::NUMBER,
+                        token.line(),
+                        token.column(),
+                        token.offset(),
+                        Some(Literal::Integer(42)),
+                    );
+                    *expression = Expression::LiteralExp(LiteralExp::new(literal));
+                }
+                expression => expression.accept_mut(self),
+            }
+        }
+    }
+
+    impl MutVisitor<()> for Inline {
+        fn visit_literal(&mut self, _literal: &mut LiteralExp) {}
+        fn visit_unary(&mut self, unary: &mut Unary) {
+            self.rewrite(unary.expression_mut());
+        }
+        fn visit_binary(&mut self, binary: &mut Binary) {
+            self.rewrite(binary.left_mut());
+            self.rewrite(binary.right_mut());
+        }
+        fn visit_grouping(&mut self, grouping: &mut Grouping) {
+            self.rewrite(grouping.expression_mut());
+        }
+        fn visit_variable(&mut self, _variable: &mut Variable) {}
+        fn visit_assign(&mut self, assign: &mut Assign) {
+            self.rewrite(assign.value_mut());
+        }
+        fn visit_logical(&mut self, logical: &mut Logical) {
+            self.rewrite(logical.left_mut());
+            self.rewrite(logical.right_mut());
+        }
+        fn visit_conditional(&mut self, conditional: &mut Conditional) {
+            self.rewrite(conditional.condition_mut());
+            self.rewrite(conditional.then_branch_mut());
+            self.rewrite(conditional.else_branch_mut());
+        }
+        fn visit_call(&mut self, call: &mut Call) {
+            self.rewrite(call.callee_mut());
+            for argument in call.arguments_mut() {
+                self.rewrite(argument);
+            }
+        }
+        fn visit_this(&mut self, _this: &mut This) {}
+        fn visit_get(&mut self, get: &mut Get) {
+            self.rewrite(get.object_mut());
+        }
+        fn visit_set(&mut self, set: &mut Set) {
+            self.rewrite(set.object_mut());
+            self.rewrite(set.value_mut());
+        }
+        fn visit_super(&mut self, _expression: &mut Super) {}
+        fn visit_interpolation(&mut self, interpolation: &mut Interpolation) {
+            for part in interpolation.parts_mut() {
+                self.rewrite(part);
+            }
+        }
+        fn visit_update(&mut self, update: &mut Update) {
+            if let Some(value) = update.value_mut() {
+                self.rewrite(value);
+            }
+        }
+    }
+
+    impl StmtMutVisitor<()> for Inline {
+        fn visit_expression_stmt(&mut self, statement: &mut ExpressionStmt) {
+            self.rewrite(statement.expression_mut());
+        }
+        fn visit_print(&mut self, statement: &mut Print) {
+            self.rewrite(statement.expression_mut());
+        }
+        fn visit_var(&mut self, statement: &mut Var) {
+            if let Some(initializer) = statement.initializer_mut() {
+                self.rewrite(initializer);
+            }
+        }
+        fn visit_block(&mut self, block: &mut Block) {
+            for statement in block.statements_mut() {
+                statement.accept_mut(self);
+            }
+        }
+        fn visit_if(&mut self, statement: &mut If) {
+            self.rewrite(statement.condition_mut());
+            statement.then_branch_mut().accept_mut(self);
+            if let Some(else_branch) = statement.else_branch_mut() {
+                else_branch.accept_mut(self);
+            }
+        }
+        fn visit_while(&mut self, statement: &mut While) {
+            self.rewrite(statement.condition_mut());
+            statement.body_mut().accept_mut(self);
+        }
+        fn visit_function(&mut self, function: &mut Function) {
+            for statement in function.body_mut() {
+                statement.accept_mut(self);
+            }
+        }
+        fn visit_return(&mut self, statement: &mut Return) {
+            if let Some(value) = statement.value_mut() {
+                self.rewrite(value);
+            }
+        }
+        fn visit_class(&mut self, class: &mut Class) {
+            for method in class.methods_mut() {
+                self.visit_function(method);
+            }
+        }
+    }
+
+    #[test]
+    fn mut_visitor_rewrites_nodes_in_place() {
+        let tokens =
+            Scanner::new("-(x + y) * f(x, \"${x}\") ? a = x : z;".to_string()).scan_tokens();
+        let mut statements = Parser::new(tokens).parse().expect("source should parse");
+        let expression = match &mut statements[0] {
+            Stmt::Expression(statement) => statement.expression_mut(),
+            _ => panic!("expected an expression statement"),
+        };
+
+        Inline.rewrite(expression);
+
+        assert_eq!(
+            AstPrinter::new().print(expression),
+            r#"(?: (* (- (group (+ 42 y))) (call f 42 (interpolate "" 42 ""))) (= a 42) z)"#
+        );
+    }
+
+    #[test]
+    fn stmt_mut_visitor_rewrites_expressions_inside_function_bodies() {
+        let source = "fun f() { if (x) return x + 1; } class A { m() { print x; } }";
+        let tokens = Scanner::new(source.to_string()).scan_tokens();
+        let mut statements = Parser::new(tokens).parse().expect("source should parse");
+        for statement in &mut statements {
+            statement.accept_mut(&mut Inline);
+        }
+
+        let mut printer = AstPrinter::new();
+        let (condition, value) = match &statements[0] {
+            Stmt::Function(function) => match &function.body()[0] {
+                Stmt::If(statement) => match statement.then_branch() {
+                    Stmt::Return(result) => (statement.condition(), result.value()),
+                    _ => panic!("expected a return statement"),
+                },
+                _ => panic!("expected an if statement"),
+            },
+            _ => panic!("expected a function declaration"),
+        };
+        assert_eq!(printer.print(condition), "42");
+        assert_eq!(
+            value.map(|value| printer.print(value)).as_deref(),
+            Some("(+ 42 1)")
+        );
+
+        let printed = match &statements[1] {
+            Stmt::Class(class) => match &class.methods()[0].body()[0] {
+                Stmt::Print(statement) => printer.print(statement.expression()),
+                _ => panic!("expected a print statement"),
+            },
+            _ => panic!("expected a class declaration"),
+        };
+        assert_eq!(printed, "42");
+    }
+}